@@ -0,0 +1,72 @@
+extern crate jni;
+
+use jni::JNIEnv;
+use jni::objects::JObject;
+use anyhow::Result;
+use crate::util::gltf::AnimationState;
+
+pub fn handle_native_init<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<()> {
+    unsafe {
+        env.set_rust_field(this, "rust_animationStateObj", AnimationState::new())?;
+    }
+    Ok(())
+}
+
+pub fn handle_native_destroy<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<()> {
+    let state: AnimationState;
+    unsafe {
+        state = env.take_rust_field(this, "rust_animationStateObj")?;
+    }
+    drop(state);
+    Ok(())
+}
+
+/// Runs `f` on the animation state stored in the `GltfAnimationState` object.
+pub fn with_animation_state<'a, R>(
+    env: &mut JNIEnv<'a>,
+    state_obj: &JObject<'a>,
+    f: impl FnOnce(&mut JNIEnv<'a>, &mut AnimationState) -> Result<R>
+) -> Result<R> {
+    let mut state: AnimationState;
+    unsafe {
+        state = env.take_rust_field(state_obj, "rust_animationStateObj")?;
+    }
+
+    let result = f(env, &mut state);
+
+    unsafe {
+        env.set_rust_field(state_obj, "rust_animationStateObj", state)?;
+    }
+
+    result
+}
+
+/// Runs `f` on the animation state stored in the `GltfAnimationState` object,
+/// or on None if the object is null.
+pub fn with_optional_animation_state<'a, R>(
+    env: &mut JNIEnv<'a>,
+    state_obj: &JObject<'a>,
+    f: impl FnOnce(&mut JNIEnv<'a>, Option<&AnimationState>) -> Result<R>
+) -> Result<R> {
+    if state_obj.is_null() {
+        f(env, None)
+    } else {
+        with_animation_state(env, state_obj, |env, state| f(env, Some(state)))
+    }
+}
+
+pub fn handle_reset<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<()> {
+    with_animation_state(env, this, |_, state| {
+        state.clear();
+        Ok(())
+    })
+}
@@ -0,0 +1,137 @@
+extern crate jni;
+
+use jni::JNIEnv;
+use jni::objects::{JObject, JString};
+use anyhow::{anyhow, Result};
+use image::RgbaImage;
+use jni::sys::{jint, jintArray, jobject};
+use crate::util;
+use crate::util::atlas::TextureAtlas;
+
+pub fn handle_native_init<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    padding: jint,
+    max_page_size: jint
+) -> Result<()> {
+    if padding < 0 || max_page_size <= 0 {
+        return Err(anyhow!("The padding {} or the maximum page size {} is wrong", padding, max_page_size));
+    }
+    let atlas = TextureAtlas::new(padding as u32, max_page_size as u32)?;
+    unsafe {
+        env.set_rust_field(this, "rust_atlasObj", atlas)?;
+    }
+    Ok(())
+}
+
+pub fn handle_native_destroy<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<()> {
+    let atlas: TextureAtlas;
+    unsafe {
+        atlas = env.take_rust_field(this, "rust_atlasObj")?;
+    }
+    drop(atlas);
+    Ok(())
+}
+
+/// Runs `f` on the atlas stored in the `TextureAtlas` object.
+pub fn with_atlas<'a, R>(
+    env: &mut JNIEnv<'a>,
+    atlas_obj: &JObject<'a>,
+    f: impl FnOnce(&mut JNIEnv<'a>, &mut TextureAtlas) -> Result<R>
+) -> Result<R> {
+    let mut atlas: TextureAtlas;
+    unsafe {
+        atlas = env.take_rust_field(atlas_obj, "rust_atlasObj")?;
+    }
+
+    let result = f(env, &mut atlas);
+
+    unsafe {
+        env.set_rust_field(atlas_obj, "rust_atlasObj", atlas)?;
+    }
+
+    result
+}
+
+pub fn handle_add_image<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    key_jstr: &JString,
+    image_obj: &JObject<'a>
+) -> Result<()> {
+    let key = String::from(env.get_string(key_jstr)?);
+    let image: RgbaImage;
+    unsafe {
+        image = env.take_rust_field(image_obj, "rust_imageObj")?;
+    }
+
+    let image_copy = image.clone();
+
+    unsafe {
+        env.set_rust_field(image_obj, "rust_imageObj", image)?;
+    }
+
+    with_atlas(env, this, |_, atlas| atlas.add_image(key, image_copy))
+}
+
+pub fn handle_contains<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    key_jstr: &JString
+) -> Result<bool> {
+    let key = String::from(env.get_string(key_jstr)?);
+    with_atlas(env, this, |_, atlas| Ok(atlas.contains(&key)))
+}
+
+pub fn handle_build<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<()> {
+    with_atlas(env, this, |_, atlas| atlas.build())
+}
+
+pub fn handle_get_page_count<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jint> {
+    with_atlas(env, this, |_, atlas| Ok(atlas.pages()?.len() as jint))
+}
+
+/// Returns a new `Image` holding a copy of the page.
+pub fn handle_get_page_image<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    page: jint
+) -> Result<jobject> {
+    let image = with_atlas(env, this, |_, atlas| {
+        atlas.pages()?.get(page as usize).cloned()
+            .ok_or_else(|| anyhow!("The atlas page {} is out of range", page))
+    })?;
+    Ok(super::image::new_image_object(env, image)?.into_raw())
+}
+
+/// Returns the placement of the image as [page, x, y, width, height, page width, page height],
+/// null if the image is not in the atlas.
+pub fn handle_get_placement<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    key_jstr: &JString
+) -> Result<jintArray> {
+    let key = String::from(env.get_string(key_jstr)?);
+    let data = with_atlas(env, this, |_, atlas| {
+        Ok(match atlas.placement(&key)? {
+            Some(it) => {
+                let page = &atlas.pages()?[it.page];
+                Some([it.page as u32, it.x, it.y, it.width, it.height, page.width(), page.height()])
+            }
+            None => None
+        })
+    })?;
+    match data {
+        Some(data) => util::jni::new_int_array(env, &data.map(|it| it as jint)),
+        None => Ok(JObject::null().into_raw())
+    }
+}
@@ -1,14 +1,30 @@
 extern crate jni;
 extern crate gltf;
 extern crate anyhow;
+extern crate nalgebra;
 
 use jni::JNIEnv;
 use jni::objects::{JByteArray, JObject, JString, JValue, JValueOwned};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use gltf::{buffer, image};
-use jni::sys::{jbyte, jbyteArray, jsize};
+use gltf::texture::WrappingMode;
+use jni::sys::{jboolean, jbyte, jbyteArray, jfloat, jfloatArray, jint, jintArray, jobject, jobjectArray, jsize, jstring};
+use nalgebra::Vector3;
 use crate::util;
-use crate::util::gltf::{LoadedGltfAccessor, LoadedGltf, LoadedGltfBuffer, LoadedGltfWrapper, LoadedGltfImage, LoadedGltfBufferView, LoadedGltfSampler, LoadedGltfTexture, LoadedGltfMaterial, LoadedGltfMesh};
+use crate::jniimpl::animation_state::{with_animation_state, with_optional_animation_state};
+use crate::util::gltf::animation::{AnimationInfo, AnimationTarget};
+use crate::util::gltf::asset::{AssetInfo, ObjectKind};
+use crate::util::gltf::buffer_view::{MeshoptCompressionInfo, MESHOPT_COMPRESSION_EXTENSION};
+use crate::util::gltf::draco::{DracoCompressionInfo, DRACO_MESH_COMPRESSION_EXTENSION};
+use crate::util::gltf::light;
+use crate::util::gltf::light::LightInfo;
+use crate::util::gltf::material::TextureSlot;
+use crate::util::gltf::node;
+use crate::util::gltf::node::InstancingInfo;
+use crate::util::gltf::scene::SceneInfo;
+use crate::util::gltf::mesh;
+use crate::util::gltf::mesh::PrimitiveInfo;
+use crate::util::gltf::{bake_albedo, AnimationState, collect_draw_items, pack_material_maps, ChannelLayout, LoadedGltfAccessor, LoadedGltf, LoadedGltfBuffer, LoadedGltfWrapper, LoadedGltfImage, LoadedGltfBufferView, LoadedGltfSampler, LoadedGltfTexture, LoadedGltfMaterial, LoadedGltfMesh, LoadedGltfNode, Constraint, IkTarget, LookAtConstraint, LookAtTarget, TwoBoneIkConstraint};
 
 pub fn get_native_callback<'a>(
     env: &mut JNIEnv<'a>,
@@ -72,40 +88,30 @@ pub fn get_initial_gltf_data<'a>(
 fn init_gltf<'a>(
     env: &mut JNIEnv<'a>,
     this: &JObject<'a>
-) {
-    let gltf_data = get_initial_gltf_data(env, this);
-    match gltf_data {
-        Ok(gltf_data) => {
-            let gltf_data_len = env.get_array_length(&gltf_data).unwrap();
-            let mut gltf_data_vec: Vec<jbyte> = util::new_buffer_vec(gltf_data_len as usize, 0);
-            env.get_byte_array_region(&gltf_data, 0, gltf_data_vec.as_mut_slice()).unwrap();
-            let gltf_data_u8: Vec<_> = gltf_data_vec.iter().map(|it| *it as u8).collect();
-            let gltf_obj = gltf::Gltf::from_slice(gltf_data_u8.as_slice());
-            match gltf_obj {
-                Ok(gltf_obj) => {
-                    unsafe {
-                        env.set_rust_field(this, "rust_gltfObj", gltf_obj).unwrap()
-                    }
-                }
-                Err(err) => {
-                    util::jni::clear_exception_if_occurred(env);
-                    util::jni::throw_runtime_exception(env, &format!("Failed to create the glTF object: {}", err)).unwrap();
-                }
-            }
-        }
-        Err(_) => {
-            ()
-        }
+) -> Result<()> {
+    let gltf_data = get_initial_gltf_data(env, this)?;
+    let gltf_data_len = env.get_array_length(&gltf_data)?;
+    let mut gltf_data_vec: Vec<jbyte> = util::new_buffer_vec(gltf_data_len as usize, 0);
+    env.get_byte_array_region(&gltf_data, 0, gltf_data_vec.as_mut_slice())?;
+    let gltf_data_u8: Vec<_> = gltf_data_vec.iter().map(|it| *it as u8).collect();
+    let gltf_obj = util::gltf::parse_gltf(gltf_data_u8.as_slice())
+        .map_err(anyhow::Error::from)
+        .and_then(|it| util::gltf::check_required_extensions(&it).map(|_| it).map_err(Into::into))
+        .and_then(|it| util::gltf::validate_gltf(&it).map(|_| it).map_err(Into::into))
+        .map_err(|err| anyhow!("Failed to create the glTF object: {}", err))?;
+    unsafe {
+        env.set_rust_field(this, "rust_gltfObj", gltf_obj)?;
     }
+    Ok(())
 }
 
 pub fn load_gltf<'a>(
     env: &mut JNIEnv<'a>,
     this: &JObject<'a>
-) {
+) -> Result<()> {
     let gltf_obj: gltf::Gltf;
     unsafe {
-        gltf_obj = env.take_rust_field(this, "rust_gltfObj").unwrap();
+        gltf_obj = env.take_rust_field(this, "rust_gltfObj")?;
     }
 
     let loaded_gltf = LoadedGltf::new();
@@ -156,81 +162,96 @@ pub fn load_gltf<'a>(
     13. glTF load finishing-up works (from 12)
      */
 
+    // Load the asset metadata.
+    loaded_gltf_wrapper.get().lock().unwrap().set_asset(AssetInfo::new_from_document(&gltf_obj));
+
     // Load buffers.
-    gltf_obj.buffers().for_each(|it| {
+    for it in gltf_obj.buffers() {
         let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
         if let buffer::Source::Uri(uri) = it.source() {
-            let uri_jstr = env.new_string(uri).unwrap();
+            let uri_jstr = env.new_string(uri)?;
             let data_arr = invoke_native_callback(
                 env, this, "loadBufferFromURI", "(Ljava/lang/String;)[B",
-                &[JValue::Object(&uri_jstr)]);
-            match data_arr {
-                Ok(data_arr) => {
-                    let data_arr = JByteArray::from(data_arr.l().unwrap());
-                    let data_arr_len = env.get_array_length(&data_arr).unwrap();
-                    let mut data = util::new_buffer_vec(data_arr_len as usize, 0);
-                    env.get_byte_array_region(data_arr, 0, data.as_mut_slice()).unwrap();
-                    let buf = LoadedGltfBuffer::new(
-                        loaded_gltf_wrapper.get(), it.index(), String::from(uri),
-                        data.iter().map(|x| *x as u8).collect());
-                    loaded_gltf.buffers_mut().push(buf);
-                }
-                Err(err) => {
-                    util::jni::clear_exception_if_occurred(env);
-                    util::jni::throw_runtime_exception(
-                        env, &format!("Failed to load glTF buffer: {}", err)).unwrap();
-                    return;
-                }
-            }
+                &[JValue::Object(&uri_jstr)])
+                .map_err(|err| anyhow!("Failed to load glTF buffer {}: {}", it.index(), err))?;
+            let data_arr = JByteArray::from(data_arr.l()?);
+            let data_arr_len = env.get_array_length(&data_arr)?;
+            let mut data = util::new_buffer_vec(data_arr_len as usize, 0);
+            env.get_byte_array_region(data_arr, 0, data.as_mut_slice())?;
+            let mut buf = LoadedGltfBuffer::new(
+                loaded_gltf_wrapper.get(), it.index(), String::from(uri),
+                data.iter().map(|x| *x as u8).collect());
+            buf.set_extras(util::gltf::extras_to_string(it.extras()));
+            loaded_gltf.buffers_mut().push(buf);
+        } else {
+            // The GLB-stored buffer, or a fallback buffer without any data
+            // (as used by EXT_meshopt_compression), which is still pushed
+            // to keep the buffer indices in place.
+            let data = match (it.index(), &gltf_obj.blob) {
+                (0, Some(blob)) => blob.clone(),
+                _ => Vec::new()
+            };
+            let mut buf = LoadedGltfBuffer::new(
+                loaded_gltf_wrapper.get(), it.index(), String::new(), data);
+            buf.set_extras(util::gltf::extras_to_string(it.extras()));
+            loaded_gltf.buffers_mut().push(buf);
         }
-    });
+    }
 
     // Load buffer views.
-    gltf_obj.views().for_each(|it| {
+    for it in gltf_obj.views() {
         let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
-        let loaded_buffer_view = LoadedGltfBufferView::new_from_view(
+        let mut loaded_buffer_view = LoadedGltfBufferView::new_from_view(
             loaded_gltf_wrapper.get(), &it);
+        if let Some(ext) = it.extension_value(MESHOPT_COMPRESSION_EXTENSION) {
+            let data = MeshoptCompressionInfo::new_from_json(ext)
+                .and_then(|info| info.decode(&loaded_gltf))
+                .map_err(|err| anyhow!("Failed to decode glTF buffer view {}: {}", it.index(), err))?;
+            loaded_buffer_view.set_decoded_data(Some(data));
+        }
         loaded_gltf.buffer_views_mut().push(loaded_buffer_view);
-    });
+    }
 
     // Load accessors.
-    gltf_obj.accessors().for_each(|it| {
+    for it in gltf_obj.accessors() {
         let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
-        let loaded_accessor = LoadedGltfAccessor::new_from_accessor(
-            loaded_gltf_wrapper.get(), &it).unwrap();
+        let loaded_accessor = LoadedGltfAccessor::new_from_accessor(loaded_gltf_wrapper.get(), &it)
+            .ok_or_else(|| anyhow!("Failed to load glTF accessor {}", it.index()))?;
         loaded_gltf.accessors_mut().push(loaded_accessor);
-    });
+    }
+
+    // Decode Draco compressed primitives into their accessors.
+    for it in gltf_obj.meshes().flat_map(|it| it.primitives()) {
+        if let Some(ext) = it.extension_value(DRACO_MESH_COMPRESSION_EXTENSION) {
+            let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
+            DracoCompressionInfo::new_from_json(ext)
+                .and_then(|info| info.decode_into_accessors(&mut loaded_gltf, &it))
+                .map_err(|err| anyhow!("Failed to decode glTF Draco primitive: {}", err))?;
+        }
+    }
 
     // Load images.
-    gltf_obj.images().for_each(|it| {
+    for it in gltf_obj.images() {
         let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
         if let image::Source::Uri { uri, mime_type } = it.source() {
             let mime_type = mime_type.unwrap_or("");
-            let uri_jstr = env.new_string(uri).unwrap();
-            let mime_type_jstr = env.new_string(mime_type).unwrap();
+            let uri_jstr = env.new_string(uri)?;
+            let mime_type_jstr = env.new_string(mime_type)?;
             let data_arr = invoke_native_callback(
                 env, this, "loadImageFromURI", "(Ljava/lang/String;Ljava/lang/String;)[B",
-                &[JValue::Object(&uri_jstr), JValue::Object(&mime_type_jstr)]);
-            match data_arr {
-                Ok(data_arr) => {
-                    let data_arr = JByteArray::from(data_arr.l().unwrap());
-                    let data_arr_len = env.get_array_length(&data_arr).unwrap();
-                    let mut data = util::new_buffer_vec(data_arr_len as usize, 0);
-                    env.get_byte_array_region(data_arr, 0, data.as_mut_slice()).unwrap();
-                    let img = LoadedGltfImage::new(
-                        loaded_gltf_wrapper.get(), it.index(), String::from(uri),
-                        data.iter().map(|x| *x as u8).collect());
-                    loaded_gltf.images_mut().push(img);
-                }
-                Err(err) => {
-                    util::jni::clear_exception_if_occurred(env);
-                    util::jni::throw_runtime_exception(
-                        env, &format!("Failed to load glTF buffer: {}", err)).unwrap();
-                    return;
-                }
-            }
+                &[JValue::Object(&uri_jstr), JValue::Object(&mime_type_jstr)])
+                .map_err(|err| anyhow!("Failed to load glTF image {}: {}", it.index(), err))?;
+            let data_arr = JByteArray::from(data_arr.l()?);
+            let data_arr_len = env.get_array_length(&data_arr)?;
+            let mut data = util::new_buffer_vec(data_arr_len as usize, 0);
+            env.get_byte_array_region(data_arr, 0, data.as_mut_slice())?;
+            let mut img = LoadedGltfImage::new(
+                loaded_gltf_wrapper.get(), it.index(), String::from(uri),
+                data.iter().map(|x| *x as u8).collect());
+            img.set_extras(util::gltf::extras_to_string(it.extras()));
+            loaded_gltf.images_mut().push(img);
         }
-    });
+    }
 
     // Load samplers.
     gltf_obj.samplers().for_each(|it| {
@@ -264,77 +285,130 @@ pub fn load_gltf<'a>(
         loaded_gltf.meshes_mut().push(loaded_mesh);
     });
 
+    // Load material variants.
+    if let Some(variants) = gltf_obj.extension_value(mesh::MATERIALS_VARIANTS_EXTENSION)
+        .and_then(|it| it.get("variants"))
+        .and_then(|it| it.as_array()) {
+        let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
+        variants.iter().for_each(|it| {
+            let name = it.get("name").and_then(|it| it.as_str()).unwrap_or("");
+            loaded_gltf.variants_mut().push(String::from(name));
+        });
+    }
+
+    // Load punctual lights.
+    if let Some(ext) = gltf_obj.extension_value(light::LIGHTS_PUNCTUAL_EXTENSION) {
+        let lights = light::read_lights(ext)?;
+        *loaded_gltf_wrapper.get().lock().unwrap().lights_mut() = lights;
+    }
+
     // Load cameras.
     // TODO
 
     // Load nodes.
-    // TODO
+    gltf_obj.nodes().for_each(|it| {
+        let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
+        let loaded_node = LoadedGltfNode::new_from_node(
+            loaded_gltf_wrapper.get(), &it);
+        loaded_gltf.nodes_mut().push(loaded_node);
+    });
+    loaded_gltf_wrapper.get().lock().unwrap().link_node_parents();
+
+    // Load GPU instances of nodes.
+    gltf_obj.nodes().for_each(|it| {
+        if let Some(ext) = it.extension_value(node::MESH_GPU_INSTANCING_EXTENSION) {
+            let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
+            let instances = InstancingInfo::new_from_json(ext).read_instances(&loaded_gltf);
+            loaded_gltf.nodes_mut()[it.index()].set_instances(instances);
+        }
+    });
 
     // Load animations.
-    // TODO
+    for it in gltf_obj.animations() {
+        let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
+        let animation = AnimationInfo::new_from_animation(&it, &loaded_gltf)
+            .map_err(|err| anyhow!("Failed to load glTF animation {}: {}", it.index(), err))?;
+        loaded_gltf.animations_mut().push(animation);
+    }
 
     // Load skins.
     // TODO
 
+    // Load scenes.
+    {
+        let mut loaded_gltf = loaded_gltf_wrapper.get().lock().unwrap();
+        gltf_obj.scenes().for_each(|it| loaded_gltf.scenes_mut().push(SceneInfo::new_from_scene(&it)));
+        loaded_gltf.set_default_scene_index(gltf_obj.default_scene().map(|it| it.index()));
+    }
+
     // Do glTF load finishing-up works.
     // TODO
 
     unsafe {
-        env.set_rust_field(this, "rust_loadedGltfObj", loaded_gltf_wrapper).unwrap_or_else(|err| {
-            util::jni::clear_exception_if_occurred(env);
-            util::jni::throw_runtime_exception(
-                env, &format!("Failed to set rust object rust_loadedGltfObj: {}", err)).unwrap()
-        });
-        env.set_rust_field(this, "rust_gltfObj", gltf_obj).unwrap_or_else(|err| {
-            util::jni::clear_exception_if_occurred(env);
-            util::jni::throw_runtime_exception(
-                env, &format!("Failed to set rust object rust_gltfObj: {}", err)).unwrap()
-        });
+        env.set_rust_field(this, "rust_loadedGltfObj", loaded_gltf_wrapper)
+            .map_err(|err| anyhow!("Failed to set rust object rust_loadedGltfObj: {}", err))?;
+        env.set_rust_field(this, "rust_gltfObj", gltf_obj)
+            .map_err(|err| anyhow!("Failed to set rust object rust_gltfObj: {}", err))?;
     }
+    Ok(())
 }
 
 pub fn announce_gltf_image_uris<'a>(
     env: &mut JNIEnv<'a>,
     this: &JObject<'a>
-) {
+) -> Result<()> {
     let gltf_obj: gltf::Gltf;
     unsafe {
-        gltf_obj = env.take_rust_field(this, "rust_gltfObj").unwrap();
+        gltf_obj = env.take_rust_field(this, "rust_gltfObj")?;
     }
 
-    gltf_obj.images().for_each(|it| {
+    let result = gltf_obj.images().try_for_each(|it| {
         if let image::Source::Uri { uri, mime_type: _ } = it.source() {
-            let uri_jstr = env.new_string(uri).unwrap();
+            let uri_jstr = env.new_string(uri)?;
             invoke_native_callback(env, this, "receiveImageURI", "(Ljava/lang/String;)V",
-                                   &[JValue::Object(&uri_jstr)]).unwrap();
+                                   &[JValue::Object(&uri_jstr)])?;
         }
+        Ok(())
     });
 
     unsafe {
-        env.set_rust_field(this, "rust_gltfObj", gltf_obj).unwrap_or_else(|err| {
-            util::jni::clear_exception_if_occurred(env);
-            util::jni::throw_runtime_exception(
-                env, &format!("Failed to set rust object rust_gltfObj: {}", err)).unwrap()
-        });
+        env.set_rust_field(this, "rust_gltfObj", gltf_obj)
+            .map_err(|err| anyhow!("Failed to set rust object rust_gltfObj: {}", err))?;
     }
+    result
+}
+
+/// Parses, validates and loads the glTF, stopping at the first error.
+fn init_and_load_gltf<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<()> {
+    init_gltf(env, this)?;
+    load_gltf(env, this)?;
+    announce_gltf_image_uris(env, this)
 }
 
+/// Throws a single exception if the glTF fails to load, in which case the native
+/// objects are left unset.
 pub fn handle_native_init<'a>(
     env: &mut JNIEnv<'a>,
     this: &JObject<'a>
 ) {
-    init_gltf(env, this);
-    load_gltf(env, this);
-    announce_gltf_image_uris(env, this);
+    if let Err(err) = init_and_load_gltf(env, this) {
+        util::jni::clear_exception_if_occurred(env);
+        util::jni::throw_runtime_exception(env, &format!("Failed to load the glTF: {}", err)).unwrap();
+    }
 }
 
 pub fn handle_native_destroy<'a>(
     env: &mut JNIEnv<'a>,
     this: &JObject<'a>
 ) {
+    // The objects are unset if the initialisation failed.
     unsafe {
-        let gltf_obj: gltf::Gltf = env.take_rust_field(&this, "rust_gltfObj").unwrap();
-        drop(gltf_obj);
+        if let Ok(gltf_obj) = env.take_rust_field::<_, _, gltf::Gltf>(this, "rust_gltfObj") {
+            drop(gltf_obj);
+        }
     }
 }
 
@@ -379,3 +453,1027 @@ pub fn handle_get_image_data_by_uri<'a>(
     env.set_byte_array_region(&jresult, 0, result_jbyte.as_slice()).unwrap();
     jresult.as_raw()
 }
+
+/// Runs the given closure on the loaded glTF of this object, taking the rust
+/// field out for the duration of the call and putting it back afterwards.
+pub fn with_loaded_gltf<'a, R>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    f: impl FnOnce(&mut JNIEnv<'a>, &mut LoadedGltf) -> Result<R>
+) -> Result<R> {
+    let loaded_gltf_obj: LoadedGltfWrapper;
+    unsafe {
+        loaded_gltf_obj = env.take_rust_field(this, "rust_loadedGltfObj")?;
+    }
+
+    let result = {
+        let mut loaded_gltf = loaded_gltf_obj.get().lock().unwrap();
+        f(env, &mut loaded_gltf)
+    };
+
+    unsafe {
+        env.set_rust_field(this, "rust_loadedGltfObj", loaded_gltf_obj)?;
+    }
+
+    result
+}
+
+fn node_index_by_name_jstr<'a>(
+    env: &mut JNIEnv<'a>,
+    loaded_gltf: &LoadedGltf,
+    name_jstr: &JString
+) -> Result<usize> {
+    let name = String::from(env.get_string(name_jstr)?);
+    loaded_gltf.node_index_by_name(&name)
+        .ok_or_else(|| anyhow!("No node named {} within the glTF", name))
+}
+
+pub fn handle_add_look_at_constraint<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    node_name_jstr: &JString,
+    min_yaw: jfloat,
+    max_yaw: jfloat,
+    min_pitch: jfloat,
+    max_pitch: jfloat
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |env, loaded_gltf| {
+        let node_index = node_index_by_name_jstr(env, loaded_gltf, node_name_jstr)?;
+        let constraint = LookAtConstraint::new(
+            node_index, (min_yaw, max_yaw), (min_pitch, max_pitch));
+        Ok(loaded_gltf.constraints_mut().add(Constraint::LookAt(constraint)) as jint)
+    })
+}
+
+pub fn handle_add_two_bone_ik_constraint<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    root_name_jstr: &JString,
+    mid_name_jstr: &JString,
+    end_name_jstr: &JString
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |env, loaded_gltf| {
+        let root_index = node_index_by_name_jstr(env, loaded_gltf, root_name_jstr)?;
+        let mid_index = node_index_by_name_jstr(env, loaded_gltf, mid_name_jstr)?;
+        let end_index = node_index_by_name_jstr(env, loaded_gltf, end_name_jstr)?;
+        let constraint = TwoBoneIkConstraint::new_from_chain(loaded_gltf, root_index, mid_index, end_index)?;
+        Ok(loaded_gltf.constraints_mut().add(Constraint::TwoBoneIk(constraint)) as jint)
+    })
+}
+
+/// Sets the look-at angles of the instance, the constraints of the glTF being shared by its instances.
+pub fn handle_set_look_at_angles<'a>(
+    env: &mut JNIEnv<'a>,
+    state_obj: &JObject<'a>,
+    yaw: jfloat,
+    pitch: jfloat
+) -> Result<()> {
+    with_animation_state(env, state_obj, |_, state| {
+        state.constraint_targets_mut().set_look_at(LookAtTarget::Angles(yaw, pitch));
+        Ok(())
+    })
+}
+
+/// Sets the IK target of the instance, the constraints of the glTF being shared by its instances.
+pub fn handle_set_ik_target<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    state_obj: &JObject<'a>,
+    constraint_id: jint,
+    target: (jfloat, jfloat, jfloat),
+    pole: (jfloat, jfloat, jfloat)
+) -> Result<()> {
+    with_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            match loaded_gltf.constraints().get(constraint_id as usize) {
+                Some(Constraint::TwoBoneIk(_)) => {
+                    state.constraint_targets_mut().set_ik(constraint_id as usize, Some(IkTarget {
+                        target: Vector3::new(target.0, target.1, target.2),
+                        pole: Vector3::new(pole.0, pole.1, pole.2)
+                    }));
+                    Ok(())
+                }
+                _ => Err(anyhow!("Constraint {} is not a two-bone IK constraint", constraint_id))
+            }
+        })
+    })
+}
+
+/// Returns the world matrices of all nodes (16 floats each, column-major)
+/// of the animated pose of the instance post-processed by the constraints.
+pub fn handle_evaluate_pose<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    state_obj: &JObject<'a>
+) -> Result<jfloatArray> {
+    let matrices = with_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            let pose = loaded_gltf.evaluate_pose(state);
+            Ok(pose.world_matrices(loaded_gltf))
+        })
+    })?;
+    let data: Vec<jfloat> = matrices.iter().flat_map(|it| it.iter().copied()).collect();
+    util::jni::new_float_array(env, &data)
+}
+
+/// Resolves the material index, -1 standing for the default material
+/// (the one of the primitives without a material).
+fn material_at<'b, 'c>(
+    loaded_gltf: &'b LoadedGltf<'c>,
+    index: jint
+) -> Result<&'b LoadedGltfMaterial<'c>> {
+    let material = if index == -1 {
+        loaded_gltf.default_material()
+    } else {
+        loaded_gltf.materials().get(index as usize)
+    };
+    material.ok_or_else(|| anyhow!("Material index {} is out of bounds", index))
+}
+
+fn primitive_at<'b>(
+    loaded_gltf: &'b LoadedGltf,
+    mesh_index: jint,
+    primitive_index: jint
+) -> Result<&'b PrimitiveInfo> {
+    loaded_gltf.primitive(mesh_index as usize, primitive_index as usize)
+        .ok_or_else(|| anyhow!("Primitive {} of mesh {} does not exist", primitive_index, mesh_index))
+}
+
+pub fn handle_get_material_count<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.materials().len() as jint)
+    })
+}
+
+pub fn handle_get_material_alpha_mode<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    index: jint
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(util::gltf::alpha_mode_to_id(material_at(loaded_gltf, index)?.alpha_mode()))
+    })
+}
+
+/// Returns the alpha cutoff of the material, as animated in the state if it is not null.
+pub fn handle_get_material_alpha_cutoff<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    index: jint,
+    state_obj: &JObject<'a>
+) -> Result<jfloat> {
+    with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            Ok(material_at(loaded_gltf, index)?.factors(state).alpha_cutoff)
+        })
+    })
+}
+
+pub fn handle_is_material_double_sided<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    index: jint
+) -> Result<jboolean> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(util::jni::bool_to_jboolean(material_at(loaded_gltf, index)?.double_sided()))
+    })
+}
+
+/// Returns null if the material has no name.
+pub fn handle_get_material_name<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    index: jint
+) -> Result<jstring> {
+    let name = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(material_at(loaded_gltf, index)?.name())
+    })?;
+    match name {
+        Some(name) => Ok(env.new_string(name)?.as_raw()),
+        None => Ok(std::ptr::null_mut())
+    }
+}
+
+/// Returns the UV matrix (9 floats, column-major) of the texture reference in the
+/// given slot of the material, as animated in the state if it is not null, or an
+/// empty array if there is no such reference.
+pub fn handle_get_texture_transform_matrix<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    material_index: jint,
+    slot_id: jint,
+    state_obj: &JObject<'a>
+) -> Result<jfloatArray> {
+    let slot = TextureSlot::from_id(slot_id)
+        .ok_or_else(|| anyhow!("The texture slot id {} is wrong", slot_id))?;
+    let matrix = with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            Ok(material_at(loaded_gltf, material_index)?
+                .animated_texture_reference(slot, state)
+                .map(|it| it.uv_matrix()))
+        })
+    })?;
+    let data: Vec<jfloat> = match matrix {
+        Some(matrix) => matrix.iter().copied().collect(),
+        None => Vec::new()
+    };
+    util::jni::new_float_array(env, &data)
+}
+
+/// Returns the (u, v) pairs of the texCoord set used by the primitive's base colour
+/// texture, with its texture transform (as animated in the state if it is not null)
+/// baked in if asked to.
+pub fn handle_get_primitive_tex_coords<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint,
+    primitive_index: jint,
+    bake_texture_transform: jboolean,
+    state_obj: &JObject<'a>
+) -> Result<jfloatArray> {
+    let tex_coords = with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            let primitive = primitive_at(loaded_gltf, mesh_index, primitive_index)?;
+            let reference = primitive.material()
+                .and_then(|it| loaded_gltf.materials().get(it))
+                .and_then(|it| it.animated_texture_reference(TextureSlot::BaseColor, state));
+            let set = reference.map(|it| it.tex_coord()).unwrap_or(0);
+            let texture_transform = if util::jni::jboolean_to_bool(bake_texture_transform) {
+                reference.and_then(|it| it.texture_transform())
+            } else {
+                None
+            };
+            Ok(loaded_gltf.read_tex_coords(primitive, set, texture_transform).unwrap_or_default())
+        })
+    })?;
+    let data: Vec<jfloat> = tex_coords.iter().flat_map(|it| [it.x, it.y]).collect();
+    util::jni::new_float_array(env, &data)
+}
+
+/// Returns the texture coordinates the primitive's base colour texture samples, texture transform
+/// baked, mapped into the atlas space of its image; null if they cannot be (see
+/// `TextureAtlas::remap_tex_coords`), the image then having to be bound as its own texture.
+/// The material is the one the primitive uses for the variant, its default one if the name is null,
+/// and its texture transform the one animated in the state if it is not null.
+pub fn handle_get_primitive_atlas_tex_coords<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint,
+    primitive_index: jint,
+    atlas_obj: &JObject<'a>,
+    variant_name_jstr: &JString,
+    state_obj: &JObject<'a>
+) -> Result<jfloatArray> {
+    let variant_name = if variant_name_jstr.is_null() {
+        None
+    } else {
+        Some(String::from(env.get_string(variant_name_jstr)?))
+    };
+    let tex_coords = with_optional_animation_state(env, state_obj, |env, state| {
+        crate::jniimpl::atlas::with_atlas(env, atlas_obj, |env, atlas| {
+            with_loaded_gltf(env, this, |_, loaded_gltf| {
+                let primitive = primitive_at(loaded_gltf, mesh_index, primitive_index)?;
+                let reference = match loaded_gltf.primitive_material(primitive, variant_name.as_deref())
+                    .and_then(|it| it.animated_texture_reference(TextureSlot::BaseColor, state)) {
+                    Some(reference) => reference,
+                    None => return Ok(None)
+                };
+                let (key, sampler) = match (
+                    loaded_gltf.texture_image_uri(reference.texture_index()),
+                    loaded_gltf.texture_sampler(reference.texture_index())
+                ) {
+                    (Some(key), Some(sampler)) => (key, sampler),
+                    _ => return Ok(None)
+                };
+                let tex_coords = loaded_gltf.read_tex_coords(
+                    primitive, reference.tex_coord(), reference.texture_transform()).unwrap_or_default();
+                atlas.remap_tex_coords(
+                    key,
+                    &tex_coords,
+                    sampler.wrap_s() == WrappingMode::ClampToEdge,
+                    sampler.wrap_t() == WrappingMode::ClampToEdge)
+            })
+        })
+    })?;
+    match tex_coords {
+        Some(tex_coords) => {
+            let data: Vec<jfloat> = tex_coords.iter().flat_map(|it| [it.x, it.y]).collect();
+            util::jni::new_float_array(env, &data)
+        }
+        None => Ok(JObject::null().into_raw())
+    }
+}
+
+pub fn handle_is_material_unlit<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    index: jint
+) -> Result<jboolean> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(util::jni::bool_to_jboolean(material_at(loaded_gltf, index)?.unlit()))
+    })
+}
+
+pub fn handle_set_unlit_full_bright<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    full_bright: jboolean
+) -> Result<()> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        loaded_gltf.set_unlit_full_bright(util::jni::jboolean_to_bool(full_bright));
+        Ok(())
+    })
+}
+
+pub fn handle_get_primitive_shading_hint<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint,
+    primitive_index: jint
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        let primitive = primitive_at(loaded_gltf, mesh_index, primitive_index)?;
+        Ok(loaded_gltf.primitive_shading_hint(primitive).id())
+    })
+}
+
+pub fn handle_get_unhandled_material_extensions<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jobjectArray> {
+    let names = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.unhandled_material_extensions())
+    })?;
+    util::jni::new_string_array(env, &names)
+}
+
+pub fn handle_get_mesh_count<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.meshes().len() as jint)
+    })
+}
+
+pub fn handle_get_primitive_count<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        let mesh = loaded_gltf.meshes().get(mesh_index as usize)
+            .ok_or_else(|| anyhow!("Mesh index {} is out of bounds", mesh_index))?;
+        Ok(mesh.primitives().len() as jint)
+    })
+}
+
+pub fn handle_get_variant_names<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jobjectArray> {
+    let names = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.variants().clone())
+    })?;
+    util::jni::new_string_array(env, &names)
+}
+
+/// Returns -1 if the primitive has no material for the variant. A null variant
+/// name resolves the default material.
+pub fn handle_get_primitive_material_for_variant<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint,
+    primitive_index: jint,
+    variant_name_jstr: &JString
+) -> Result<jint> {
+    let variant_name = if variant_name_jstr.is_null() {
+        None
+    } else {
+        Some(String::from(env.get_string(variant_name_jstr)?))
+    };
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        let primitive = primitive_at(loaded_gltf, mesh_index, primitive_index)?;
+        Ok(loaded_gltf.primitive_material_for_variant(primitive, variant_name.as_deref())
+            .map(|it| it as jint)
+            .unwrap_or(-1))
+    })
+}
+
+/// Returns the render class of the primitive for the variant (the default material if
+/// the variant name is null) as the render category id, the shading hint id, the
+/// index of the texture to bind (-1 if there is none), 1 if an emissive layer has to
+/// be drawn over it (0 otherwise) and the index of its texture (-1 if there is none).
+pub fn handle_get_primitive_render_class<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint,
+    primitive_index: jint,
+    variant_name_jstr: &JString,
+    state_obj: &JObject<'a>
+) -> Result<jintArray> {
+    let variant_name = if variant_name_jstr.is_null() {
+        None
+    } else {
+        Some(String::from(env.get_string(variant_name_jstr)?))
+    };
+    let render_class = with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            let primitive = primitive_at(loaded_gltf, mesh_index, primitive_index)?;
+            Ok(loaded_gltf.primitive_render_class(primitive, variant_name.as_deref(), state))
+        })
+    })?;
+    util::jni::new_int_array(env, &[
+        render_class.category().id(),
+        render_class.shading_hint().id(),
+        render_class.texture_index().map(|it| it as jint).unwrap_or(-1),
+        render_class.emissive_layer() as jint,
+        render_class.emissive_texture_index().map(|it| it as jint).unwrap_or(-1)
+    ])
+}
+
+/// Returns null if the texture has no image loaded from a URI.
+pub fn handle_get_texture_image_uri<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    texture_index: jint
+) -> Result<jstring> {
+    let uri = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.texture_image_uri(texture_index as usize).cloned())
+    })?;
+    new_optional_string(env, uri)
+}
+
+/// Returns the OpenGL sampler parameters of the texture, see `LoadedGltfSampler::gl_parameters`.
+pub fn handle_get_texture_sampler_parameters<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    texture_index: jint
+) -> Result<jintArray> {
+    let parameters = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        loaded_gltf.texture_sampler(texture_index as usize)
+            .map(|it| it.gl_parameters())
+            .ok_or_else(|| anyhow!("Texture index {} is out of bounds", texture_index))
+    })?;
+    util::jni::new_int_array(env, &parameters.map(|it| it as jint))
+}
+
+/// Returns the OpenGL sampler parameters of the first texture sampling the image.
+pub fn handle_get_image_sampler_parameters<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    uri_jstr: &JString
+) -> Result<jintArray> {
+    let uri = String::from(env.get_string(uri_jstr)?);
+    let parameters = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        loaded_gltf.image_sampler(&uri)
+            .map(|it| it.gl_parameters())
+            .ok_or_else(|| anyhow!("No image is loaded from URI {}", uri))
+    })?;
+    util::jni::new_int_array(env, &parameters.map(|it| it as jint))
+}
+
+/// Returns (colour, alpha cutoff) of the image loaded from the URI, the colour flag being 1 for
+/// sRGB colour images and 0 for data maps, and the cutoff -1 if no `MASK` material uses the image.
+pub fn handle_get_image_usage<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    uri_jstr: &JString
+) -> Result<jfloatArray> {
+    let uri = String::from(env.get_string(uri_jstr)?);
+    let usage = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        loaded_gltf.image_usage(&uri)
+            .ok_or_else(|| anyhow!("No image is loaded from URI {}", uri))
+    })?;
+    util::jni::new_float_array(env, &[
+        if usage.color() { 1.0 } else { 0.0 },
+        usage.alpha_cutoff().unwrap_or(-1.0)
+    ])
+}
+
+pub fn handle_get_node_count<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.nodes().len() as jint)
+    })
+}
+
+/// Returns -1 if the node has no mesh.
+pub fn handle_get_node_mesh_index<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    node_index: jint
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        let node = loaded_gltf.nodes().get(node_index as usize)
+            .ok_or_else(|| anyhow!("Node index {} is out of bounds", node_index))?;
+        Ok(node.mesh_index().map(|it| it as jint).unwrap_or(-1))
+    })
+}
+
+pub fn handle_get_primitive_positions<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint,
+    primitive_index: jint
+) -> Result<jfloatArray> {
+    let positions = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        let primitive = primitive_at(loaded_gltf, mesh_index, primitive_index)?;
+        Ok(loaded_gltf.read_positions(primitive).unwrap_or_default())
+    })?;
+    let data: Vec<jfloat> = positions.iter().flat_map(|it| [it.x, it.y, it.z]).collect();
+    util::jni::new_float_array(env, &data)
+}
+
+pub fn handle_get_primitive_normals<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint,
+    primitive_index: jint
+) -> Result<jfloatArray> {
+    let normals = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        let primitive = primitive_at(loaded_gltf, mesh_index, primitive_index)?;
+        Ok(loaded_gltf.read_normals(primitive).unwrap_or_default())
+    })?;
+    let data: Vec<jfloat> = normals.iter().flat_map(|it| [it.x, it.y, it.z]).collect();
+    util::jni::new_float_array(env, &data)
+}
+
+/// Returns the (r, g, b, a) colours of the COLOR_n set of the primitive, or an empty
+/// array if the primitive has no such set. The base colour factor is the one animated
+/// in the state if it is not null.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_get_primitive_colors<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint,
+    primitive_index: jint,
+    set: jint,
+    to_srgb: jboolean,
+    multiply_base_color: jboolean,
+    state_obj: &JObject<'a>
+) -> Result<jfloatArray> {
+    let colors = with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            let primitive = primitive_at(loaded_gltf, mesh_index, primitive_index)?;
+            Ok(loaded_gltf.read_colors(
+                primitive, set as usize,
+                util::jni::jboolean_to_bool(to_srgb),
+                util::jni::jboolean_to_bool(multiply_base_color),
+                state).unwrap_or_default())
+        })
+    })?;
+    let data: Vec<jfloat> = colors.iter().flat_map(|it| [it.x, it.y, it.z, it.w]).collect();
+    util::jni::new_float_array(env, &data)
+}
+
+fn light_at<'b>(loaded_gltf: &'b LoadedGltf, index: jint) -> Result<&'b LightInfo> {
+    loaded_gltf.lights().get(index as usize)
+        .ok_or_else(|| anyhow!("Light index {} is out of bounds", index))
+}
+
+/// Returns a copy of the light with the properties animated in the state, if any.
+fn animated_light_at(loaded_gltf: &LoadedGltf, index: jint, state: Option<&AnimationState>) -> Result<LightInfo> {
+    let mut light = light_at(loaded_gltf, index)?.clone();
+    if let Some(state) = state {
+        state.apply_to_light(index as usize, &mut light);
+    }
+    Ok(light)
+}
+
+fn node_at<'b, 'c>(
+    loaded_gltf: &'b LoadedGltf<'c>,
+    index: jint
+) -> Result<&'b LoadedGltfNode<'c>> {
+    loaded_gltf.nodes().get(index as usize)
+        .ok_or_else(|| anyhow!("Node index {} is out of bounds", index))
+}
+
+pub fn handle_get_light_count<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.lights().len() as jint)
+    })
+}
+
+/// Returns null if the light has no name.
+pub fn handle_get_light_name<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    light_index: jint
+) -> Result<jstring> {
+    let name = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(light_at(loaded_gltf, light_index)?.name())
+    })?;
+    match name {
+        Some(name) => Ok(env.new_string(name)?.as_raw()),
+        None => Ok(std::ptr::null_mut())
+    }
+}
+
+pub fn handle_get_light_type<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    light_index: jint
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(light_at(loaded_gltf, light_index)?.light_type().id())
+    })
+}
+
+/// Returns the colour of the light, as animated in the state if it is not null.
+pub fn handle_get_light_color<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    light_index: jint,
+    state_obj: &JObject<'a>
+) -> Result<jfloatArray> {
+    let color = with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            Ok(animated_light_at(loaded_gltf, light_index, state)?.color())
+        })
+    })?;
+    util::jni::new_float_array(env, color.as_slice())
+}
+
+/// Returns the intensity of the light, as animated in the state if it is not null.
+pub fn handle_get_light_intensity<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    light_index: jint,
+    state_obj: &JObject<'a>
+) -> Result<jfloat> {
+    with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            Ok(animated_light_at(loaded_gltf, light_index, state)?.intensity())
+        })
+    })
+}
+
+/// Returns positive infinity if the range is not defined. The range is the one
+/// animated in the state if it is not null.
+pub fn handle_get_light_range<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    light_index: jint,
+    state_obj: &JObject<'a>
+) -> Result<jfloat> {
+    with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            Ok(animated_light_at(loaded_gltf, light_index, state)?.range().unwrap_or(jfloat::INFINITY))
+        })
+    })
+}
+
+/// Returns the inner and outer cone angles, in radians, as animated in the state if it is not null.
+pub fn handle_get_light_cone_angles<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    light_index: jint,
+    state_obj: &JObject<'a>
+) -> Result<jfloatArray> {
+    let angles = with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            let light = animated_light_at(loaded_gltf, light_index, state)?;
+            Ok([light.inner_cone_angle(), light.outer_cone_angle()])
+        })
+    })?;
+    util::jni::new_float_array(env, &angles)
+}
+
+/// Returns -1 if the node has no light.
+pub fn handle_get_node_light_index<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    node_index: jint
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(node_at(loaded_gltf, node_index)?.light_index().map(|it| it as jint).unwrap_or(-1))
+    })
+}
+
+/// Returns the world-space position of the node in the pose of the instance with the constraints applied.
+pub fn handle_get_node_world_position<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    state_obj: &JObject<'a>,
+    node_index: jint
+) -> Result<jfloatArray> {
+    let position = with_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            node_at(loaded_gltf, node_index)?;
+            let pose = loaded_gltf.evaluate_pose(state);
+            Ok(pose.world_position(loaded_gltf, node_index as usize))
+        })
+    })?;
+    util::jni::new_float_array(env, position.as_slice())
+}
+
+/// Returns the world-space direction of the -Z axis of the node, which is the
+/// direction of its light, in the pose of the instance with the constraints applied.
+pub fn handle_get_node_world_direction<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    state_obj: &JObject<'a>,
+    node_index: jint
+) -> Result<jfloatArray> {
+    let direction = with_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            node_at(loaded_gltf, node_index)?;
+            let pose = loaded_gltf.evaluate_pose(state);
+            Ok(pose.world_forward(loaded_gltf, node_index as usize))
+        })
+    })?;
+    util::jni::new_float_array(env, direction.as_slice())
+}
+
+pub fn handle_get_node_instance_count<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    node_index: jint
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(node_at(loaded_gltf, node_index)?.instances().len() as jint)
+    })
+}
+
+/// Returns the local matrices of the node's instances, 16 floats each in column-major order.
+pub fn handle_get_node_instance_matrices<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    node_index: jint
+) -> Result<jfloatArray> {
+    let data: Vec<jfloat> = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(node_at(loaded_gltf, node_index)?.instances().iter()
+            .flat_map(|it| it.matrix().iter().copied().collect::<Vec<_>>())
+            .collect())
+    })?;
+    util::jni::new_float_array(env, &data)
+}
+
+/// Returns the draw items of the pose of the instance with the constraints applied, 19 floats
+/// each: the node index, the mesh index, the instance index (-1 if the instances are not
+/// expanded) and the world matrix in column-major order.
+pub fn handle_get_draw_items<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    state_obj: &JObject<'a>,
+    expand_instances: jboolean
+) -> Result<jfloatArray> {
+    let data: Vec<jfloat> = with_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            let pose = loaded_gltf.evaluate_pose(state);
+            let items = collect_draw_items(
+                loaded_gltf, &pose, util::jni::jboolean_to_bool(expand_instances));
+            Ok(items.iter().flat_map(|it| {
+                let mut item = vec![
+                    it.node_index() as jfloat,
+                    it.mesh_index() as jfloat,
+                    it.instance_index().map(|it| it as jfloat).unwrap_or(-1.0)
+                ];
+                item.extend(it.world_matrix().iter());
+                item
+            }).collect())
+        })
+    })?;
+    util::jni::new_float_array(env, &data)
+}
+
+pub fn handle_get_animation_count<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jint> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.animations().len() as jint)
+    })
+}
+
+/// Returns null if the animation has no name.
+pub fn handle_get_animation_name<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    animation_index: jint
+) -> Result<jstring> {
+    let name = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        loaded_gltf.animations().get(animation_index as usize)
+            .map(|it| it.name())
+            .ok_or_else(|| anyhow!("Animation index {} is out of bounds", animation_index))
+    })?;
+    match name {
+        Some(name) => Ok(env.new_string(name)?.as_raw()),
+        None => Ok(std::ptr::null_mut())
+    }
+}
+
+pub fn handle_get_animation_duration<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    animation_index: jint
+) -> Result<jfloat> {
+    with_loaded_gltf(env, this, |_, loaded_gltf| {
+        loaded_gltf.animations().get(animation_index as usize)
+            .map(|it| it.duration())
+            .ok_or_else(|| anyhow!("Animation index {} is out of bounds", animation_index))
+    })
+}
+
+/// Samples the animation at the time (in seconds) into the animation state of an instance.
+pub fn handle_sample_animation<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    state_obj: &JObject<'a>,
+    animation_index: jint,
+    time: jfloat
+) -> Result<()> {
+    with_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            if loaded_gltf.sample_animation(state, animation_index as usize, time) {
+                Ok(())
+            } else {
+                Err(anyhow!("Animation index {} is out of bounds", animation_index))
+            }
+        })
+    })
+}
+
+/// Returns the animated value of the property the `KHR_animation_pointer` JSON pointer
+/// points at in the animation state of an instance, or an empty array if no sampled animation
+/// has written it.
+pub fn handle_get_animated_value<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    state_obj: &JObject<'a>,
+    pointer_jstr: &JString
+) -> Result<jfloatArray> {
+    let pointer = String::from(env.get_string(pointer_jstr)?);
+    let value = with_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            let target = AnimationTarget::new_from_pointer(&pointer, loaded_gltf)?
+                .ok_or_else(|| anyhow!("The animation pointer {} cannot be animated", pointer))?;
+            Ok(state.value(target).cloned().unwrap_or_default())
+        })
+    })?;
+    util::jni::new_float_array(env, &value)
+}
+
+fn new_optional_string<'a>(env: &mut JNIEnv<'a>, value: Option<String>) -> Result<jstring> {
+    match value {
+        Some(value) => Ok(env.new_string(value)?.as_raw()),
+        None => Ok(std::ptr::null_mut())
+    }
+}
+
+/// Returns the `extras` of the object as JSON text, or null if it has none.
+pub fn handle_get_extras<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    kind_id: jint,
+    index: jint
+) -> Result<jstring> {
+    let extras = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        let kind = ObjectKind::from_id(kind_id)
+            .ok_or_else(|| anyhow!("The object kind id {} is wrong", kind_id))?;
+        loaded_gltf.object_extras(kind, index as usize)
+            .map(|it| it.cloned())
+            .ok_or_else(|| anyhow!("{:?} index {} is out of bounds or not loaded", kind, index))
+    })?;
+    new_optional_string(env, extras)
+}
+
+/// Returns the `extras` of the primitive as JSON text, or null if it has none.
+pub fn handle_get_primitive_extras<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mesh_index: jint,
+    primitive_index: jint
+) -> Result<jstring> {
+    let extras = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(primitive_at(loaded_gltf, mesh_index, primitive_index)?.extras().cloned())
+    })?;
+    new_optional_string(env, extras)
+}
+
+/// Returns the root `extras` as JSON text, or null if there are none.
+pub fn handle_get_root_extras<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jstring> {
+    let extras = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.asset().extras().cloned())
+    })?;
+    new_optional_string(env, extras)
+}
+
+pub fn handle_get_asset_generator<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jstring> {
+    let generator = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.asset().generator().cloned())
+    })?;
+    new_optional_string(env, generator)
+}
+
+pub fn handle_get_asset_version<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jstring> {
+    let version = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.asset().version().clone())
+    })?;
+    Ok(env.new_string(version)?.as_raw())
+}
+
+pub fn handle_get_asset_copyright<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jstring> {
+    let copyright = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.asset().copyright().cloned())
+    })?;
+    new_optional_string(env, copyright)
+}
+
+pub fn handle_get_asset_min_version<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jstring> {
+    let min_version = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.asset().min_version().cloned())
+    })?;
+    new_optional_string(env, min_version)
+}
+
+pub fn handle_get_extensions_used<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jobjectArray> {
+    let names = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.asset().extensions_used().clone())
+    })?;
+    util::jni::new_string_array(env, &names)
+}
+
+pub fn handle_get_extensions_required<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jobjectArray> {
+    let names = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.asset().extensions_required().clone())
+    })?;
+    util::jni::new_string_array(env, &names)
+}
+
+/// Returns the extensions the glTF uses but does not require which are not supported.
+pub fn handle_get_ignored_extensions<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jobjectArray> {
+    let names = with_loaded_gltf(env, this, |_, loaded_gltf| {
+        Ok(loaded_gltf.asset().ignored_extensions())
+    })?;
+    util::jni::new_string_array(env, &names)
+}
+
+/// Returns the PNG-encoded albedo texture baked from the PBR maps of the material,
+/// with the factors animated in the state if it is not null.
+pub fn handle_bake_material_albedo<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    material_index: jint,
+    state_obj: &JObject<'a>
+) -> Result<jbyteArray> {
+    let data = with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            let image = bake_albedo(loaded_gltf, material_at(loaded_gltf, material_index)?, state)?;
+            util::image::encode_png(&image)
+        })
+    })?;
+    Ok(env.byte_array_from_slice(&data)?.as_raw())
+}
+
+/// Returns a new `Image` with the maps of the material packed into the channel layout,
+/// with the factors animated in the state if it is not null.
+pub fn handle_pack_material_maps<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    material_index: jint,
+    layout_id: jint,
+    state_obj: &JObject<'a>
+) -> Result<jobject> {
+    let layout = ChannelLayout::from_id(layout_id)
+        .ok_or_else(|| anyhow!("The channel layout id {} is wrong", layout_id))?;
+    let image = with_optional_animation_state(env, state_obj, |env, state| {
+        with_loaded_gltf(env, this, |_, loaded_gltf| {
+            pack_material_maps(loaded_gltf, material_at(loaded_gltf, material_index)?, layout, state)
+        })
+    })?;
+    Ok(crate::jniimpl::image::new_image_object(env, image)?.into_raw())
+}
@@ -2,15 +2,16 @@ extern crate jni;
 extern crate image;
 extern crate lazy_static;
 
-use std::io::Cursor;
 use std::sync::Mutex;
 use jni::JNIEnv;
 use jni::objects::{JByteArray, JClass, JObject};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use image::RgbaImage;
-use jni::sys::{jbyte, jbyteArray, jint, jsize, jstring};
+use jni::sys::{jboolean, jbyte, jbyteArray, jfloat, jint, jobject, jsize, jstring};
 use crate::util;
 use crate::util::error_message::ErrorMessage;
+use crate::util::image::{ImageUtilError, PowerOfTwoRounding, ResizeFilter};
+use crate::util::mipmap::{MipmapFilter, MipmapOptions};
 
 lazy_static::lazy_static! {
     pub static ref ERROR_MESSAGE: Mutex<ErrorMessage> = Mutex::new(ErrorMessage::new());
@@ -31,15 +32,8 @@ pub fn handle_native_init<'a>(
     let mut data: Vec<jbyte> = util::new_buffer_vec(raw_data_len as usize, 0);
     env.get_byte_array_region(raw_data, 0, data.as_mut_slice())?;
     let data_u8: Vec<u8> = data.iter().map(|x| *x as u8).collect();
-    let image = image::io::Reader::new(Cursor::new(data_u8.as_slice()))
-        .with_guessed_format()?.decode()?;
-    let rgba8_image: RgbaImage = image.to_rgba8();
-
-    unsafe {
-        env.set_rust_field(this, "rust_imageObj", rgba8_image)?;
-    }
-
-    Ok(())
+    let levels = util::image::decode_image_levels(data_u8.as_slice(), None)?;
+    set_image_levels(env, this, levels)
 }
 
 pub fn handle_native_init_with_format<'a>(
@@ -54,28 +48,55 @@ pub fn handle_native_init_with_format<'a>(
     
     let format = util::image::image_format_from_id(format_id)?;
     let data_u8: Vec<u8> = data.iter().map(|x| *x as u8).collect();
-    let mut image_reader = image::io::Reader::new(
-        Cursor::new(data_u8.as_slice()));
-    image_reader.set_format(format);
-    let image = image_reader.decode()?;
-    let rgba8_image: RgbaImage = image.to_rgba8();
+    let levels = util::image::decode_image_levels(data_u8.as_slice(), Some(format))?;
+    set_image_levels(env, this, levels)
+}
+
+/// Stores the full-sized image in `rust_imageObj` and the smaller mip levels,
+/// if any, in `rust_imageMipLevelsObj`.
+fn set_image_levels<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    mut levels: Vec<RgbaImage>
+) -> Result<()> {
+    if levels.is_empty() {
+        return Err(anyhow!("The image has no level"));
+    }
+    let rgba8_image = levels.remove(0);
 
     unsafe {
         env.set_rust_field(this, "rust_imageObj", rgba8_image)?;
+        env.set_rust_field(this, "rust_imageMipLevelsObj", levels)?;
     }
 
     Ok(())
 }
 
+/// The JNI name of the Kotlin `Image` class.
+const IMAGE_CLASS: &str = "top/srcres/mods/modelassetlib/image/Image";
+
+/// Creates a Kotlin `Image` holding the image, through its private no-argument constructor.
+pub fn new_image_object<'a>(
+    env: &mut JNIEnv<'a>,
+    image: RgbaImage
+) -> Result<JObject<'a>> {
+    let obj = env.new_object(IMAGE_CLASS, "()V", &[])?;
+    set_image_levels(env, &obj, vec![image])?;
+    Ok(obj)
+}
+
 pub fn handle_native_destroy<'a>(
     env: &mut JNIEnv<'a>,
     this: &JObject<'a>
 ) -> Result<()> {
     let image: RgbaImage;
+    let mip_levels: Vec<RgbaImage>;
     unsafe {
         image = env.take_rust_field(this, "rust_imageObj")?;
+        mip_levels = env.take_rust_field(this, "rust_imageMipLevelsObj")?;
     }
     drop(image);
+    drop(mip_levels);
     Ok(())
 }
 
@@ -159,3 +180,193 @@ pub fn handle_get_rgba_data<'a>(
 
     Ok(data_jarr.as_raw())
 }
+
+/// Runs `f` on the mip level of the image, level 0 being the full-sized image.
+fn with_mip_level<'a, R>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    level: jint,
+    f: impl FnOnce(&mut JNIEnv<'a>, &RgbaImage) -> Result<R>
+) -> Result<R> {
+    let image: RgbaImage;
+    let mip_levels: Vec<RgbaImage>;
+    unsafe {
+        image = env.take_rust_field(this, "rust_imageObj")?;
+        mip_levels = env.take_rust_field(this, "rust_imageMipLevelsObj")?;
+    }
+
+    let result = match level {
+        0 => f(env, &image),
+        _ if level > 0 && (level as usize) <= mip_levels.len() => f(env, &mip_levels[level as usize - 1]),
+        _ => Err(anyhow!("The mip level {} is out of range", level))
+    };
+
+    unsafe {
+        env.set_rust_field(this, "rust_imageObj", image)?;
+        env.set_rust_field(this, "rust_imageMipLevelsObj", mip_levels)?;
+    }
+
+    result
+}
+
+pub fn handle_get_mip_level_count<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>
+) -> Result<jint> {
+    let mip_levels: Vec<RgbaImage>;
+    unsafe {
+        mip_levels = env.take_rust_field(this, "rust_imageMipLevelsObj")?;
+    }
+
+    let result = mip_levels.len() + 1;
+
+    unsafe {
+        env.set_rust_field(this, "rust_imageMipLevelsObj", mip_levels)?;
+    }
+
+    Ok(result as jint)
+}
+
+pub fn handle_get_mip_level_width<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    level: jint
+) -> Result<jint> {
+    with_mip_level(env, this, level, |_, image| Ok(image.width() as jint))
+}
+
+pub fn handle_get_mip_level_height<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    level: jint
+) -> Result<jint> {
+    with_mip_level(env, this, level, |_, image| Ok(image.height() as jint))
+}
+
+pub fn handle_get_mip_level_rgba_data<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    level: jint
+) -> Result<jbyteArray> {
+    with_mip_level(env, this, level, |env, image| {
+        let data: Vec<jbyte> = image.as_raw().iter().map(|x| *x as jbyte).collect();
+        let data_jarr = env.new_byte_array(data.len() as jsize)?;
+        env.set_byte_array_region(&data_jarr, 0, data.as_slice())?;
+        Ok(data_jarr.as_raw())
+    })
+}
+
+pub fn handle_extract_channel<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    channel: jint
+) -> Result<jobject> {
+    let image: RgbaImage;
+    unsafe {
+        image = env.take_rust_field(this, "rust_imageObj")?;
+    }
+
+    let extracted = util::image::extract_channel(&image, channel);
+
+    // Put the image back before constructing the new object, which may leave a Java exception pending.
+    unsafe {
+        env.set_rust_field(this, "rust_imageObj", image)?;
+    }
+
+    new_image_object(env, extracted?).map(|it| it.into_raw())
+}
+
+/// Replaces the mip levels of the image with a full chain generated from the full-sized image.
+/// A negative alpha cutoff disables the alpha coverage preservation.
+pub fn handle_generate_mipmaps<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    filter_id: jint,
+    gamma_correct: jboolean,
+    alpha_cutoff: jfloat
+) -> Result<()> {
+    let options = MipmapOptions {
+        filter: MipmapFilter::from_id(filter_id)?,
+        gamma_correct: util::jni::jboolean_to_bool(gamma_correct),
+        alpha_cutoff: if alpha_cutoff < 0.0 { None } else { Some(alpha_cutoff) }
+    };
+
+    let image: RgbaImage;
+    let mip_levels: Vec<RgbaImage>;
+    unsafe {
+        image = env.take_rust_field(this, "rust_imageObj")?;
+        mip_levels = env.take_rust_field(this, "rust_imageMipLevelsObj")?;
+    }
+    drop(mip_levels);
+
+    let mip_levels = util::mipmap::generate_mip_levels(&image, options);
+
+    unsafe {
+        env.set_rust_field(this, "rust_imageObj", image)?;
+        env.set_rust_field(this, "rust_imageMipLevelsObj", mip_levels)?;
+    }
+
+    Ok(())
+}
+
+/// Replaces the full-sized image with the one `f` makes of it, dropping the mip levels
+/// since they would no longer match it.
+fn replace_image<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    f: impl FnOnce(&RgbaImage) -> Result<RgbaImage>
+) -> Result<()> {
+    let image: RgbaImage;
+    let mip_levels: Vec<RgbaImage>;
+    unsafe {
+        image = env.take_rust_field(this, "rust_imageObj")?;
+        mip_levels = env.take_rust_field(this, "rust_imageMipLevelsObj")?;
+    }
+
+    let (image, mip_levels, result) = match f(&image) {
+        Ok(replaced) => (replaced, Vec::new(), Ok(())),
+        Err(err) => (image, mip_levels, Err(err))
+    };
+
+    unsafe {
+        env.set_rust_field(this, "rust_imageObj", image)?;
+        env.set_rust_field(this, "rust_imageMipLevelsObj", mip_levels)?;
+    }
+
+    result
+}
+
+pub fn handle_resize<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    width: jint,
+    height: jint,
+    filter_id: jint,
+    gamma_correct: jboolean
+) -> Result<()> {
+    if width <= 0 || height <= 0 {
+        return Err(ImageUtilError::WrongSize(width, height).into());
+    }
+    let filter = ResizeFilter::from_id(filter_id)?;
+    replace_image(env, this, |image| Ok(util::image::resize(
+        image, width as u32, height as u32, filter, util::jni::jboolean_to_bool(gamma_correct))))
+}
+
+/// Resamples the image to its normalised size, see `util::image::normalized_size`.
+/// A maximum size of 0 or less means no maximum.
+pub fn handle_normalize_size<'a>(
+    env: &mut JNIEnv<'a>,
+    this: &JObject<'a>,
+    rounding_id: jint,
+    max_size: jint,
+    filter_id: jint,
+    gamma_correct: jboolean
+) -> Result<()> {
+    let rounding = PowerOfTwoRounding::from_id(rounding_id)?;
+    let filter = ResizeFilter::from_id(filter_id)?;
+    let max_size = if max_size > 0 { Some(max_size as u32) } else { None };
+    replace_image(env, this, |image| {
+        let (width, height) = util::image::normalized_size(image.width(), image.height(), rounding, max_size);
+        Ok(util::image::resize(image, width, height, filter, util::jni::jboolean_to_bool(gamma_correct)))
+    })
+}
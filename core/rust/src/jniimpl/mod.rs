@@ -1,2 +1,4 @@
 pub mod gltf;
 pub mod image;
+pub mod atlas;
+pub mod animation_state;
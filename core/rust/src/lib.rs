@@ -2,7 +2,7 @@ extern crate jni;
 
 use jni::JNIEnv;
 use jni::objects::{JByteArray, JClass, JObject, JString};
-use jni::sys::{jboolean, jbyteArray, jint, jstring};
+use jni::sys::{jboolean, jbyteArray, jfloat, jfloatArray, jint, jintArray, jobject, jobjectArray, jstring};
 
 pub mod jniimpl;
 pub mod util;
@@ -48,6 +48,870 @@ pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getImageData
     jniimpl::gltf::handle_get_image_data_by_uri(&mut env, &this, &uri)
 }
 
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_addLookAtConstraint<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    node_name: JString,
+    min_yaw: jfloat,
+    max_yaw: jfloat,
+    min_pitch: jfloat,
+    max_pitch: jfloat
+) -> jint {
+    jniimpl::gltf::handle_add_look_at_constraint(
+        &mut env, &this, &node_name, min_yaw, max_yaw, min_pitch, max_pitch).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_add_look_at_constraint failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_addTwoBoneIkConstraint<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    root_name: JString,
+    mid_name: JString,
+    end_name: JString
+) -> jint {
+    jniimpl::gltf::handle_add_two_bone_ik_constraint(
+        &mut env, &this, &root_name, &mid_name, &end_name).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_add_two_bone_ik_constraint failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_setLookAtAngles<'local>(
+    mut env: JNIEnv<'local>,
+    _this: JObject<'local>,
+    state: JObject<'local>,
+    yaw: jfloat,
+    pitch: jfloat
+) {
+    jniimpl::gltf::handle_set_look_at_angles(&mut env, &state, yaw, pitch).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_set_look_at_angles failed: {}", err)).unwrap();
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_setIkTarget<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    state: JObject<'local>,
+    constraint_id: jint,
+    target_x: jfloat,
+    target_y: jfloat,
+    target_z: jfloat,
+    pole_x: jfloat,
+    pole_y: jfloat,
+    pole_z: jfloat
+) {
+    jniimpl::gltf::handle_set_ik_target(
+        &mut env, &this, &state, constraint_id,
+        (target_x, target_y, target_z), (pole_x, pole_y, pole_z)).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_set_ik_target failed: {}", err)).unwrap();
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_evaluatePose<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    state: JObject<'local>
+) -> jfloatArray {
+    jniimpl::gltf::handle_evaluate_pose(&mut env, &this, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_evaluate_pose failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getMaterialCount<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jint {
+    jniimpl::gltf::handle_get_material_count(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_material_count failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getMaterialAlphaMode0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    index: jint
+) -> jint {
+    jniimpl::gltf::handle_get_material_alpha_mode(&mut env, &this, index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_material_alpha_mode failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getMaterialAlphaCutoff0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    index: jint,
+    state: JObject<'local>
+) -> jfloat {
+    jniimpl::gltf::handle_get_material_alpha_cutoff(&mut env, &this, index, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_material_alpha_cutoff failed: {}", err)).unwrap();
+        0.0
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_isMaterialDoubleSided<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    index: jint
+) -> jboolean {
+    jniimpl::gltf::handle_is_material_double_sided(&mut env, &this, index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_is_material_double_sided failed: {}", err)).unwrap();
+        util::jni::bool_to_jboolean(false)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getMaterialName<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    index: jint
+) -> jstring {
+    jniimpl::gltf::handle_get_material_name(&mut env, &this, index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_material_name failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getTextureTransformMatrix0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    material_index: jint,
+    slot_id: jint,
+    state: JObject<'local>
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_texture_transform_matrix(&mut env, &this, material_index, slot_id, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_texture_transform_matrix failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitiveTexCoords0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint,
+    primitive_index: jint,
+    bake_texture_transform: jboolean,
+    state: JObject<'local>
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_primitive_tex_coords(&mut env, &this, mesh_index, primitive_index, bake_texture_transform, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_tex_coords failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_isMaterialUnlit<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    index: jint
+) -> jboolean {
+    jniimpl::gltf::handle_is_material_unlit(&mut env, &this, index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_is_material_unlit failed: {}", err)).unwrap();
+        util::jni::bool_to_jboolean(false)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_setUnlitFullBright<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    full_bright: jboolean
+) {
+    jniimpl::gltf::handle_set_unlit_full_bright(&mut env, &this, full_bright).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_set_unlit_full_bright failed: {}", err)).unwrap();
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitiveShadingHint0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint,
+    primitive_index: jint
+) -> jint {
+    jniimpl::gltf::handle_get_primitive_shading_hint(&mut env, &this, mesh_index, primitive_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_shading_hint failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getUnhandledMaterialExtensions<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jobjectArray {
+    jniimpl::gltf::handle_get_unhandled_material_extensions(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_unhandled_material_extensions failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getMeshCount<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jint {
+    jniimpl::gltf::handle_get_mesh_count(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_mesh_count failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitiveCount<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint
+) -> jint {
+    jniimpl::gltf::handle_get_primitive_count(&mut env, &this, mesh_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_count failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getVariantNames<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jobjectArray {
+    jniimpl::gltf::handle_get_variant_names(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_variant_names failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitiveMaterialForVariant<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint,
+    primitive_index: jint,
+    variant_name: JString
+) -> jint {
+    jniimpl::gltf::handle_get_primitive_material_for_variant(&mut env, &this, mesh_index, primitive_index, &variant_name).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_material_for_variant failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getNodeCount<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jint {
+    jniimpl::gltf::handle_get_node_count(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_node_count failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getNodeMeshIndex<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    node_index: jint
+) -> jint {
+    jniimpl::gltf::handle_get_node_mesh_index(&mut env, &this, node_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_node_mesh_index failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitivePositions<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint,
+    primitive_index: jint
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_primitive_positions(&mut env, &this, mesh_index, primitive_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_positions failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitiveNormals<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint,
+    primitive_index: jint
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_primitive_normals(&mut env, &this, mesh_index, primitive_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_normals failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getLightCount<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jint {
+    jniimpl::gltf::handle_get_light_count(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_light_count failed: {}", err)).unwrap();
+        0
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getLightName<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    light_index: jint
+) -> jstring {
+    jniimpl::gltf::handle_get_light_name(&mut env, &this, light_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_light_name failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getLightType0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    light_index: jint
+) -> jint {
+    jniimpl::gltf::handle_get_light_type(&mut env, &this, light_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_light_type failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getLightColor0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    light_index: jint,
+    state: JObject<'local>
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_light_color(&mut env, &this, light_index, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_light_color failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getLightIntensity0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    light_index: jint,
+    state: JObject<'local>
+) -> jfloat {
+    jniimpl::gltf::handle_get_light_intensity(&mut env, &this, light_index, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_light_intensity failed: {}", err)).unwrap();
+        0.0
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getLightRange0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    light_index: jint,
+    state: JObject<'local>
+) -> jfloat {
+    jniimpl::gltf::handle_get_light_range(&mut env, &this, light_index, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_light_range failed: {}", err)).unwrap();
+        0.0
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getLightConeAngles0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    light_index: jint,
+    state: JObject<'local>
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_light_cone_angles(&mut env, &this, light_index, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_light_cone_angles failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getNodeLightIndex<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    node_index: jint
+) -> jint {
+    jniimpl::gltf::handle_get_node_light_index(&mut env, &this, node_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_node_light_index failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getNodeWorldPosition<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    state: JObject<'local>,
+    node_index: jint
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_node_world_position(&mut env, &this, &state, node_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_node_world_position failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getNodeWorldDirection<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    state: JObject<'local>,
+    node_index: jint
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_node_world_direction(&mut env, &this, &state, node_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_node_world_direction failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getNodeInstanceCount<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    node_index: jint
+) -> jint {
+    jniimpl::gltf::handle_get_node_instance_count(&mut env, &this, node_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_node_instance_count failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getNodeInstanceMatrices<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    node_index: jint
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_node_instance_matrices(&mut env, &this, node_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_node_instance_matrices failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getDrawItems0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    state: JObject<'local>,
+    expand_instances: jboolean
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_draw_items(&mut env, &this, &state, expand_instances).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_draw_items failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getAnimationCount<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jint {
+    jniimpl::gltf::handle_get_animation_count(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_animation_count failed: {}", err)).unwrap();
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getAnimationName<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    animation_index: jint
+) -> jstring {
+    jniimpl::gltf::handle_get_animation_name(&mut env, &this, animation_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_animation_name failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getAnimationDuration<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    animation_index: jint
+) -> jfloat {
+    jniimpl::gltf::handle_get_animation_duration(&mut env, &this, animation_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_animation_duration failed: {}", err)).unwrap();
+        0.0
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_sampleAnimation<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    state: JObject<'local>,
+    animation_index: jint,
+    time: jfloat
+) {
+    jniimpl::gltf::handle_sample_animation(&mut env, &this, &state, animation_index, time).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_sample_animation failed: {}", err)).unwrap();
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getAnimatedValue<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    state: JObject<'local>,
+    pointer_jstr: JString<'local>
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_animated_value(&mut env, &this, &state, &pointer_jstr).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_animated_value failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getExtras0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    kind_id: jint,
+    index: jint
+) -> jstring {
+    jniimpl::gltf::handle_get_extras(&mut env, &this, kind_id, index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_extras failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitiveExtras<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint,
+    primitive_index: jint
+) -> jstring {
+    jniimpl::gltf::handle_get_primitive_extras(&mut env, &this, mesh_index, primitive_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_extras failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getRootExtras<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jstring {
+    jniimpl::gltf::handle_get_root_extras(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_root_extras failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getAssetGenerator<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jstring {
+    jniimpl::gltf::handle_get_asset_generator(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_asset_generator failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getAssetVersion<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jstring {
+    jniimpl::gltf::handle_get_asset_version(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_asset_version failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getAssetCopyright<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jstring {
+    jniimpl::gltf::handle_get_asset_copyright(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_asset_copyright failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getAssetMinVersion<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jstring {
+    jniimpl::gltf::handle_get_asset_min_version(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_asset_min_version failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getExtensionsUsed<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jobjectArray {
+    jniimpl::gltf::handle_get_extensions_used(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_extensions_used failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getExtensionsRequired<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jobjectArray {
+    jniimpl::gltf::handle_get_extensions_required(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_extensions_required failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getIgnoredExtensions<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jobjectArray {
+    jniimpl::gltf::handle_get_ignored_extensions(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_ignored_extensions failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitiveRenderClass0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint,
+    primitive_index: jint,
+    variant_name_jstr: JString<'local>,
+    state: JObject<'local>
+) -> jintArray {
+    jniimpl::gltf::handle_get_primitive_render_class(&mut env, &this, mesh_index, primitive_index, &variant_name_jstr, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_render_class failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getTextureImageUri<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    texture_index: jint
+) -> jstring {
+    jniimpl::gltf::handle_get_texture_image_uri(&mut env, &this, texture_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_texture_image_uri failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_bakeMaterialAlbedo0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    material_index: jint,
+    state: JObject<'local>
+) -> jbyteArray {
+    jniimpl::gltf::handle_bake_material_albedo(&mut env, &this, material_index, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_bake_material_albedo failed: {}", err)).unwrap();
+        util::jni::new_empty_byte_array(&mut env)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitiveColors0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint,
+    primitive_index: jint,
+    set: jint,
+    to_srgb: jboolean,
+    multiply_base_color: jboolean,
+    state: JObject<'local>
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_primitive_colors(&mut env, &this, mesh_index, primitive_index, set, to_srgb, multiply_base_color, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_colors failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_packMaterialMaps0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    material_index: jint,
+    layout_id: jint,
+    state: JObject<'local>
+) -> jobject {
+    jniimpl::gltf::handle_pack_material_maps(&mut env, &this, material_index, layout_id, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_pack_material_maps failed: {}", err)).unwrap();
+        JObject::null().into_raw()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getTextureSamplerParameters0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    texture_index: jint
+) -> jintArray {
+    jniimpl::gltf::handle_get_texture_sampler_parameters(&mut env, &this, texture_index).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_texture_sampler_parameters failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getImageSamplerParameters0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    uri: JString<'local>
+) -> jintArray {
+    jniimpl::gltf::handle_get_image_sampler_parameters(&mut env, &this, &uri).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_image_sampler_parameters failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getImageUsage0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    uri: JString<'local>
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_image_usage(&mut env, &this, &uri).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_image_usage failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_Gltf_getPrimitiveAtlasTexCoords0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    mesh_index: jint,
+    primitive_index: jint,
+    atlas: JObject<'local>,
+    variant_name_jstr: JString<'local>,
+    state: JObject<'local>
+) -> jfloatArray {
+    jniimpl::gltf::handle_get_primitive_atlas_tex_coords(&mut env, &this, mesh_index, primitive_index, &atlas, &variant_name_jstr, &state).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::gltf::handle_get_primitive_atlas_tex_coords failed: {}", err)).unwrap();
+        std::ptr::null_mut()
+    })
+}
+
+// Class: top.srcres.mods.modelassetlib.gltf.GltfAnimationState
+// File: top/srcres/mods/modelassetlib/gltf/GltfAnimationState.kt
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_GltfAnimationState_nativeInit<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) {
+    jniimpl::animation_state::handle_native_init(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::animation_state::handle_native_init failed: {}", err)).unwrap();
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_GltfAnimationState_nativeDestroy<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) {
+    jniimpl::animation_state::handle_native_destroy(&mut env, &this).unwrap()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_gltf_GltfAnimationState_reset<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) {
+    jniimpl::animation_state::handle_reset(&mut env, &this).unwrap_or_else(|err| {
+        util::jni::throw_runtime_exception(&mut env, &format!(
+            "jniimpl::animation_state::handle_reset failed: {}", err)).unwrap();
+    })
+}
+
 // Class: top.srcres.mods.modelassetlib.image.ImageKt
 // File: top/srcres/mods/modelassetlib/image/Image.kt
 
@@ -158,3 +1022,246 @@ pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_Image_getRgbaDat
         util::jni::new_empty_byte_array(&mut env)
     })
 }
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_Image_getMipLevelCount0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jint {
+    jniimpl::image::handle_get_mip_level_count(&mut env, &this).unwrap_or_else(|err| {
+        jniimpl::image::record_error(&format!(
+            "jniimpl::image::handle_get_mip_level_count failed: {}", err));
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_Image_getMipLevelWidth0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    level: jint
+) -> jint {
+    jniimpl::image::handle_get_mip_level_width(&mut env, &this, level).unwrap_or_else(|err| {
+        jniimpl::image::record_error(&format!(
+            "jniimpl::image::handle_get_mip_level_width failed: {}", err));
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_Image_getMipLevelHeight0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    level: jint
+) -> jint {
+    jniimpl::image::handle_get_mip_level_height(&mut env, &this, level).unwrap_or_else(|err| {
+        jniimpl::image::record_error(&format!(
+            "jniimpl::image::handle_get_mip_level_height failed: {}", err));
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_Image_getMipLevelRgbaData0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    level: jint
+) -> jbyteArray {
+    jniimpl::image::handle_get_mip_level_rgba_data(&mut env, &this, level).unwrap_or_else(|err| {
+        jniimpl::image::record_error(&format!(
+            "jniimpl::image::handle_get_mip_level_rgba_data failed: {}", err));
+        util::jni::new_empty_byte_array(&mut env)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_Image_extractChannel0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    channel: jint
+) -> jobject {
+    jniimpl::image::handle_extract_channel(&mut env, &this, channel).unwrap_or_else(|err| {
+        jniimpl::image::record_error(&format!(
+            "jniimpl::image::handle_extract_channel failed: {}", err));
+        JObject::null().into_raw()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_Image_generateMipmaps0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    filter_id: jint,
+    gamma_correct: jboolean,
+    alpha_cutoff: jfloat
+) -> jboolean {
+    match jniimpl::image::handle_generate_mipmaps(&mut env, &this, filter_id, gamma_correct, alpha_cutoff) {
+        Ok(_) => {
+            util::jni::bool_to_jboolean(true)
+        }
+        Err(err) => {
+            jniimpl::image::record_error(&format!(
+                "jniimpl::image::handle_generate_mipmaps failed: {}", err));
+            util::jni::bool_to_jboolean(false)
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_Image_resize0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    width: jint,
+    height: jint,
+    filter_id: jint,
+    gamma_correct: jboolean
+) -> jboolean {
+    match jniimpl::image::handle_resize(&mut env, &this, width, height, filter_id, gamma_correct) {
+        Ok(_) => {
+            util::jni::bool_to_jboolean(true)
+        }
+        Err(err) => {
+            jniimpl::image::record_error(&format!(
+                "jniimpl::image::handle_resize failed: {}", err));
+            util::jni::bool_to_jboolean(false)
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_Image_normalizeSize0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    rounding_id: jint,
+    max_size: jint,
+    filter_id: jint,
+    gamma_correct: jboolean
+) -> jboolean {
+    match jniimpl::image::handle_normalize_size(&mut env, &this, rounding_id, max_size, filter_id, gamma_correct) {
+        Ok(_) => {
+            util::jni::bool_to_jboolean(true)
+        }
+        Err(err) => {
+            jniimpl::image::record_error(&format!(
+                "jniimpl::image::handle_normalize_size failed: {}", err));
+            util::jni::bool_to_jboolean(false)
+        }
+    }
+}
+
+// Class: top.srcres.mods.modelassetlib.image.TextureAtlas
+// File: top/srcres/mods/modelassetlib/image/TextureAtlas.kt
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_TextureAtlas_nativeInit<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    padding: jint,
+    max_page_size: jint
+) -> jboolean {
+    match jniimpl::atlas::handle_native_init(&mut env, &this, padding, max_page_size) {
+        Ok(_) => {
+            util::jni::bool_to_jboolean(true)
+        }
+        Err(err) => {
+            jniimpl::image::record_error(&format!(
+                "jniimpl::atlas::handle_native_init failed: {}", err));
+            util::jni::bool_to_jboolean(false)
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_TextureAtlas_nativeDestroy<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) {
+    jniimpl::atlas::handle_native_destroy(&mut env, &this).unwrap()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_TextureAtlas_addImage0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    key: JString<'local>,
+    image: JObject<'local>
+) -> jboolean {
+    match jniimpl::atlas::handle_add_image(&mut env, &this, &key, &image) {
+        Ok(_) => {
+            util::jni::bool_to_jboolean(true)
+        }
+        Err(err) => {
+            jniimpl::image::record_error(&format!(
+                "jniimpl::atlas::handle_add_image failed: {}", err));
+            util::jni::bool_to_jboolean(false)
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_TextureAtlas_contains0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    key: JString<'local>
+) -> jboolean {
+    jniimpl::atlas::handle_contains(&mut env, &this, &key).map(util::jni::bool_to_jboolean).unwrap_or_else(|err| {
+        jniimpl::image::record_error(&format!(
+            "jniimpl::atlas::handle_contains failed: {}", err));
+        util::jni::bool_to_jboolean(false)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_TextureAtlas_build0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jboolean {
+    match jniimpl::atlas::handle_build(&mut env, &this) {
+        Ok(_) => {
+            util::jni::bool_to_jboolean(true)
+        }
+        Err(err) => {
+            jniimpl::image::record_error(&format!(
+                "jniimpl::atlas::handle_build failed: {}", err));
+            util::jni::bool_to_jboolean(false)
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_TextureAtlas_getPageCount0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>
+) -> jint {
+    jniimpl::atlas::handle_get_page_count(&mut env, &this).unwrap_or_else(|err| {
+        jniimpl::image::record_error(&format!(
+            "jniimpl::atlas::handle_get_page_count failed: {}", err));
+        -1
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_TextureAtlas_getPageImage0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    page: jint
+) -> jobject {
+    jniimpl::atlas::handle_get_page_image(&mut env, &this, page).unwrap_or_else(|err| {
+        jniimpl::image::record_error(&format!(
+            "jniimpl::atlas::handle_get_page_image failed: {}", err));
+        JObject::null().into_raw()
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_top_srcres_mods_modelassetlib_image_TextureAtlas_getPlacement0<'local>(
+    mut env: JNIEnv<'local>,
+    this: JObject<'local>,
+    key: JString<'local>
+) -> jintArray {
+    jniimpl::atlas::handle_get_placement(&mut env, &this, &key).unwrap_or_else(|err| {
+        jniimpl::image::record_error(&format!(
+            "jniimpl::atlas::handle_get_placement failed: {}", err));
+        std::ptr::null_mut()
+    })
+}
@@ -0,0 +1,359 @@
+extern crate image;
+
+use std::collections::HashMap;
+use image::RgbaImage;
+use nalgebra::Vector2;
+use thiserror::Error;
+use anyhow::Result;
+
+/// How far outside [0, 1] texture coordinates may go and still be taken as in range,
+/// since exporters often write coordinates a rounding error past the edges.
+const TEX_COORD_EPSILON: f32 = 1.0e-4;
+
+#[derive(Error, Debug)]
+pub enum AtlasError {
+    #[error("The image {0} of {1}x{2} pixels does not fit in an atlas page of {3}x{3} pixels with its padding.")]
+    ImageTooLarge(String, u32, u32, u32),
+    #[error("The atlas has not been built.")]
+    NotBuilt,
+    #[error("The atlas page size {0} is not a power of two.")]
+    PageSizeNotPowerOfTwo(u32),
+    #[error("The image {0} is empty.")]
+    EmptyImage(String)
+}
+
+/// Where an image was placed in the atlas: its page and its rectangle in pixels,
+/// the padding around it excluded.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AtlasPlacement {
+    pub page: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32
+}
+
+/// A row of a page the images are placed side by side in, as tall as its first image.
+struct Shelf {
+    y: u32,
+    height: u32,
+    x: u32
+}
+
+/// The shelves of a page being packed and the extent they use.
+struct PageLayout {
+    shelves: Vec<Shelf>,
+    width: u32,
+    height: u32
+}
+
+impl PageLayout {
+    fn new() -> Self {
+        Self { shelves: Vec::new(), width: 0, height: 0 }
+    }
+
+    /// Finds room for a rectangle of the size, returning its position.
+    fn place(&mut self, width: u32, height: u32, max_size: u32) -> Option<(u32, u32)> {
+        let position = match self.shelves.iter_mut()
+            .find(|it| it.height >= height && it.x + width <= max_size) {
+            Some(shelf) => {
+                let position = (shelf.x, shelf.y);
+                shelf.x += width;
+                position
+            }
+            None => {
+                let y = self.shelves.last().map(|it| it.y + it.height).unwrap_or(0);
+                if y + height > max_size {
+                    return None;
+                }
+                self.shelves.push(Shelf { y, height, x: width });
+                (0, y)
+            }
+        };
+        self.width = self.width.max(position.0 + width);
+        self.height = self.height.max(position.1 + height);
+        Some(position)
+    }
+}
+
+/// Packs images into power-of-two pages, so that models can be drawn with fewer texture binds.
+///
+/// Images are added under a key (the URI of glTF images), then `build` shelf-packs them, tallest
+/// first, each surrounded by padding filled with its edge texels so that filtering and mip levels
+/// do not bleed neighbouring images in.
+pub struct TextureAtlas {
+    padding: u32,
+    max_page_size: u32,
+    images: Vec<(String, RgbaImage)>,
+    pages: Vec<RgbaImage>,
+    placements: HashMap<String, AtlasPlacement>,
+    built: bool
+}
+
+impl TextureAtlas {
+    /// The maximum page size has to be a power of two, since the pages are sized
+    /// with the power of two above what they use.
+    pub fn new(padding: u32, max_page_size: u32) -> Result<Self> {
+        if !max_page_size.is_power_of_two() {
+            return Err(AtlasError::PageSizeNotPowerOfTwo(max_page_size).into());
+        }
+        Ok(Self {
+            padding,
+            max_page_size,
+            images: Vec::new(),
+            pages: Vec::new(),
+            placements: HashMap::new(),
+            built: false
+        })
+    }
+
+    pub fn padding(&self) -> u32 {
+        self.padding
+    }
+
+    pub fn max_page_size(&self) -> u32 {
+        self.max_page_size
+    }
+
+    /// Adds the image under the key, replacing the image already under it.
+    /// The atlas has to be built again for it to show up. Empty images are refused.
+    pub fn add_image(&mut self, key: String, image: RgbaImage) -> Result<()> {
+        if image.width() == 0 || image.height() == 0 {
+            return Err(AtlasError::EmptyImage(key).into());
+        }
+        self.images.retain(|it| it.0 != key);
+        self.images.push((key, image));
+        self.built = false;
+        Ok(())
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.images.iter().any(|it| it.0 == key)
+    }
+
+    pub fn is_built(&self) -> bool {
+        self.built
+    }
+
+    /// Packs the images added so far into pages, replacing the pages of the previous build.
+    pub fn build(&mut self) -> Result<()> {
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by_key(|it| {
+            let image = &self.images[*it].1;
+            (std::cmp::Reverse(image.height()), std::cmp::Reverse(image.width()))
+        });
+
+        let mut layouts: Vec<PageLayout> = Vec::new();
+        let mut positions = vec![(0, 0, 0); self.images.len()];
+        for index in order {
+            let (key, image) = &self.images[index];
+            let width = image.width() + self.padding * 2;
+            let height = image.height() + self.padding * 2;
+            if width > self.max_page_size || height > self.max_page_size {
+                return Err(AtlasError::ImageTooLarge(
+                    key.clone(), image.width(), image.height(), self.max_page_size).into());
+            }
+            let placed = layouts.iter_mut().enumerate()
+                .find_map(|(page, it)| it.place(width, height, self.max_page_size).map(|(x, y)| (page, x, y)));
+            positions[index] = match placed {
+                Some(position) => position,
+                None => {
+                    let mut layout = PageLayout::new();
+                    let (x, y) = layout.place(width, height, self.max_page_size).unwrap();
+                    layouts.push(layout);
+                    (layouts.len() - 1, x, y)
+                }
+            };
+        }
+
+        self.pages = layouts.iter()
+            .map(|it| RgbaImage::new(it.width.next_power_of_two(), it.height.next_power_of_two()))
+            .collect();
+        self.placements.clear();
+        for ((key, image), (page, x, y)) in self.images.iter().zip(positions) {
+            blit_with_bleeding(&mut self.pages[page], image, x, y, self.padding);
+            self.placements.insert(key.clone(), AtlasPlacement {
+                page,
+                x: x + self.padding,
+                y: y + self.padding,
+                width: image.width(),
+                height: image.height()
+            });
+        }
+        self.built = true;
+        Ok(())
+    }
+
+    pub fn pages(&self) -> Result<&Vec<RgbaImage>> {
+        if self.built { Ok(&self.pages) } else { Err(AtlasError::NotBuilt.into()) }
+    }
+
+    pub fn placement(&self, key: &str) -> Result<Option<&AtlasPlacement>> {
+        if self.built { Ok(self.placements.get(key)) } else { Err(AtlasError::NotBuilt.into()) }
+    }
+
+    /// Maps the texture coordinates of the image under the key into the atlas space of its page.
+    ///
+    /// An atlas cannot repeat an image, so the coordinates have to stay within [0, 1]. Out of range
+    /// coordinates are clamped along the axes the sampler clamps to the edge (which samples the same
+    /// texels), while along repeating axes they make the remap fail, returning None: the image then
+    /// has to keep being bound as its own texture. None is returned too if the key is not in the atlas.
+    pub fn remap_tex_coords(
+        &self,
+        key: &str,
+        tex_coords: &[Vector2<f32>],
+        clamp_s: bool,
+        clamp_t: bool
+    ) -> Result<Option<Vec<Vector2<f32>>>> {
+        let placement = match self.placement(key)? {
+            Some(placement) => placement,
+            None => return Ok(None)
+        };
+        let page = &self.pages[placement.page];
+        let in_range = |it: f32| (-TEX_COORD_EPSILON..=1.0 + TEX_COORD_EPSILON).contains(&it);
+        if tex_coords.iter().any(|it| (!clamp_s && !in_range(it.x)) || (!clamp_t && !in_range(it.y))) {
+            return Ok(None);
+        }
+        let offset = Vector2::new(placement.x as f32 / page.width() as f32, placement.y as f32 / page.height() as f32);
+        let scale = Vector2::new(
+            placement.width as f32 / page.width() as f32, placement.height as f32 / page.height() as f32);
+        Ok(Some(tex_coords.iter()
+            .map(|it| offset + it.map(|c| c.clamp(0.0, 1.0)).component_mul(&scale))
+            .collect()))
+    }
+}
+
+/// Copies the image into the page with its top left corner at `x + padding, y + padding`,
+/// filling the padding around it with its edge texels.
+fn blit_with_bleeding(page: &mut RgbaImage, image: &RgbaImage, x: u32, y: u32, padding: u32) {
+    for dy in 0..image.height() + padding * 2 {
+        for dx in 0..image.width() + padding * 2 {
+            let sx = dx.saturating_sub(padding).min(image.width() - 1);
+            let sy = dy.saturating_sub(padding).min(image.height() - 1);
+            page.put_pixel(x + dx, y + dy, *image.get_pixel(sx, sy));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+    use super::*;
+
+    fn filled(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    fn overlap(a: &AtlasPlacement, b: &AtlasPlacement, padding: u32) -> bool {
+        a.page == b.page
+            && a.x < b.x + b.width + padding * 2 && b.x < a.x + a.width + padding * 2
+            && a.y < b.y + b.height + padding * 2 && b.y < a.y + a.height + padding * 2
+    }
+
+    #[test]
+    fn page_sizes_have_to_be_powers_of_two() {
+        assert!(TextureAtlas::new(1, 1000).is_err());
+        assert!(TextureAtlas::new(1, 0).is_err());
+        assert!(TextureAtlas::new(1, 1024).is_ok());
+    }
+
+    #[test]
+    fn empty_images_are_refused() {
+        let mut atlas = TextureAtlas::new(0, 64).unwrap();
+        assert!(atlas.add_image("empty".to_string(), RgbaImage::new(0, 4)).is_err());
+        assert!(!atlas.contains("empty"));
+    }
+
+    #[test]
+    fn build_places_images_without_overlap_on_power_of_two_pages() {
+        let padding = 2;
+        let mut atlas = TextureAtlas::new(padding, 64).unwrap();
+        let sizes = [(30, 20, 10), (12, 28, 20), (16, 16, 30), (40, 8, 40), (60, 60, 50)];
+        for (i, (width, height, value)) in sizes.iter().enumerate() {
+            atlas.add_image(i.to_string(), filled(*width, *height, *value)).unwrap();
+        }
+        assert!(atlas.placement("0").is_err());
+        atlas.build().unwrap();
+
+        let pages = atlas.pages().unwrap();
+        assert!(pages.len() > 1);
+        for page in pages {
+            assert!(page.width().is_power_of_two() && page.height().is_power_of_two());
+            assert!(page.width() <= 64 && page.height() <= 64);
+        }
+        let placements: Vec<AtlasPlacement> = (0..sizes.len())
+            .map(|it| *atlas.placement(&it.to_string()).unwrap().unwrap())
+            .collect();
+        for (i, (placement, (width, height, value))) in placements.iter().zip(sizes).enumerate() {
+            assert_eq!((placement.width, placement.height), (width, height));
+            let page = &pages[placement.page];
+            assert!(placement.x + width + padding <= page.width());
+            assert!(placement.y + height + padding <= page.height());
+            // The image and the padding bled out of its corner hold its texels.
+            assert_eq!(page.get_pixel(placement.x, placement.y)[0], value);
+            assert_eq!(page.get_pixel(placement.x - padding, placement.y - padding)[0], value);
+            for other in &placements[i + 1..] {
+                assert!(!overlap(placement, other, padding), "{:?} overlaps {:?}", placement, other);
+            }
+        }
+    }
+
+    #[test]
+    fn build_fails_on_images_too_large_for_a_page() {
+        let mut atlas = TextureAtlas::new(1, 32).unwrap();
+        atlas.add_image("large".to_string(), filled(31, 4, 0)).unwrap();
+        assert!(atlas.build().is_err());
+    }
+
+    #[test]
+    fn adding_an_image_invalidates_the_build() {
+        let mut atlas = TextureAtlas::new(0, 32).unwrap();
+        atlas.add_image("a".to_string(), filled(4, 4, 0)).unwrap();
+        atlas.build().unwrap();
+        assert!(atlas.is_built());
+        atlas.add_image("b".to_string(), filled(4, 4, 0)).unwrap();
+        assert!(!atlas.is_built());
+        assert!(atlas.pages().is_err());
+    }
+
+    #[test]
+    fn remap_tex_coords_maps_into_the_placement() {
+        let mut atlas = TextureAtlas::new(0, 32).unwrap();
+        atlas.add_image("a".to_string(), filled(16, 32, 0)).unwrap();
+        atlas.add_image("b".to_string(), filled(16, 16, 0)).unwrap();
+        atlas.build().unwrap();
+        let placement = *atlas.placement("b").unwrap().unwrap();
+        assert_eq!(atlas.pages().unwrap()[0].dimensions(), (32, 32));
+
+        let remapped = atlas.remap_tex_coords(
+            "b", &[Vector2::new(0.0, 0.0), Vector2::new(1.0, 1.0), Vector2::new(0.5, 0.25)], false, false)
+            .unwrap().unwrap();
+        let origin = Vector2::new(placement.x as f32 / 32.0, placement.y as f32 / 32.0);
+        assert_eq!(remapped[0], origin);
+        assert_eq!(remapped[1], origin + Vector2::new(0.5, 0.5));
+        assert_eq!(remapped[2], origin + Vector2::new(0.25, 0.125));
+    }
+
+    #[test]
+    fn remap_tex_coords_clamps_only_along_clamped_axes() {
+        let mut atlas = TextureAtlas::new(0, 32).unwrap();
+        atlas.add_image("a".to_string(), filled(32, 32, 0)).unwrap();
+        atlas.build().unwrap();
+        let out_in_s = [Vector2::new(1.5, 0.5)];
+
+        assert_eq!(atlas.remap_tex_coords("a", &out_in_s, false, true).unwrap(), None);
+        assert_eq!(atlas.remap_tex_coords("a", &out_in_s, true, false).unwrap(), Some(vec![Vector2::new(1.0, 0.5)]));
+        // Coordinates a rounding error out of range are taken as in range.
+        assert_eq!(
+            atlas.remap_tex_coords("a", &[Vector2::new(1.00001, -0.00001)], false, false).unwrap(),
+            Some(vec![Vector2::new(1.0, 0.0)]));
+    }
+
+    #[test]
+    fn remap_tex_coords_of_missing_keys_is_none() {
+        let mut atlas = TextureAtlas::new(0, 32).unwrap();
+        assert!(atlas.remap_tex_coords("a", &[], true, true).is_err());
+        atlas.build().unwrap();
+        assert_eq!(atlas.remap_tex_coords("a", &[Vector2::new(0.5, 0.5)], true, true).unwrap(), None);
+    }
+}
@@ -23,6 +23,12 @@ impl Bool {
     }
 }
 
+impl Default for ErrorMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ErrorMessage {
     pub fn new() -> Self {
         Self {
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use nalgebra::{Quaternion, UnitQuaternion, Vector2, Vector3, Vector4};
+use super::{ConstraintTargets, GltfVecNum, LoadedGltf, NodePose};
+use super::animation::{AnimationInfo, AnimationTarget, LightProperty, MaterialProperty, NodeProperty};
+use super::light::LightInfo;
+use super::material::{MaterialFactors, TextureSlot, TextureTransformInfo};
+
+/// The values the sampled animations of one glTF instance have written, over its rest state.
+///
+/// Node channels end up in the pose, while the material and light values written
+/// by `KHR_animation_pointer` channels are kept as overrides for the renderer to read.
+/// The targets of the constraints the pose is post-processed with are kept here too.
+#[derive(Clone, Default)]
+pub struct AnimationState {
+    node_values: HashMap<(usize, NodeProperty), Vec<GltfVecNum>>,
+    material_values: HashMap<(usize, MaterialProperty), Vec<GltfVecNum>>,
+    light_values: HashMap<(usize, LightProperty), Vec<GltfVecNum>>,
+    constraint_targets: ConstraintTargets
+}
+
+impl AnimationState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets every animated value, going back to the rest state.
+    /// The constraint targets are kept.
+    pub fn clear(&mut self) {
+        self.node_values.clear();
+        self.material_values.clear();
+        self.light_values.clear();
+    }
+
+    pub fn constraint_targets(&self) -> &ConstraintTargets {
+        &self.constraint_targets
+    }
+
+    pub fn constraint_targets_mut(&mut self) -> &mut ConstraintTargets {
+        &mut self.constraint_targets
+    }
+
+    /// Samples the animation at the time and writes its values, overwriting
+    /// the values of the same targets other animations wrote before.
+    pub fn apply_animation(&mut self, animation: &AnimationInfo, time: GltfVecNum) {
+        for (target, value) in animation.sample(time) {
+            self.set_value(target, value);
+        }
+    }
+
+    pub fn set_value(&mut self, target: AnimationTarget, value: Vec<GltfVecNum>) {
+        match target {
+            AnimationTarget::Node(index, property) => self.node_values.insert((index, property), value),
+            AnimationTarget::Material(index, property) => self.material_values.insert((index, property), value),
+            AnimationTarget::Light(index, property) => self.light_values.insert((index, property), value)
+        };
+    }
+
+    /// Returns the animated value of the target, None if it is not animated.
+    pub fn value(&self, target: AnimationTarget) -> Option<&Vec<GltfVecNum>> {
+        match target {
+            AnimationTarget::Node(index, property) => self.node_values.get(&(index, property)),
+            AnimationTarget::Material(index, property) => self.material_values.get(&(index, property)),
+            AnimationTarget::Light(index, property) => self.light_values.get(&(index, property))
+        }
+    }
+
+    pub fn material_value(&self, index: usize, property: MaterialProperty) -> Option<&Vec<GltfVecNum>> {
+        self.material_values.get(&(index, property))
+    }
+
+    pub fn light_value(&self, index: usize, property: LightProperty) -> Option<&Vec<GltfVecNum>> {
+        self.light_values.get(&(index, property))
+    }
+
+    /// Writes the animated factors of the material over the factors.
+    pub fn apply_to_material_factors(&self, index: usize, factors: &mut MaterialFactors) {
+        for ((material_index, property), value) in &self.material_values {
+            if *material_index != index || value.len() != property.component_count() {
+                continue;
+            }
+            match property {
+                MaterialProperty::BaseColorFactor =>
+                    factors.base_color_factor = Vector4::new(value[0], value[1], value[2], value[3]),
+                MaterialProperty::MetallicFactor => factors.metallic_factor = value[0],
+                MaterialProperty::RoughnessFactor => factors.roughness_factor = value[0],
+                MaterialProperty::EmissiveFactor =>
+                    factors.emissive_factor = Vector3::new(value[0], value[1], value[2]),
+                MaterialProperty::EmissiveStrength => factors.emissive_strength = value[0],
+                MaterialProperty::AlphaCutoff => factors.alpha_cutoff = value[0],
+                MaterialProperty::NormalScale => factors.normal_scale = value[0],
+                MaterialProperty::OcclusionStrength => factors.occlusion_strength = value[0],
+                MaterialProperty::TextureOffset(_)
+                | MaterialProperty::TextureRotation(_)
+                | MaterialProperty::TextureScale(_) => {}
+            }
+        }
+    }
+
+    /// Returns the texture transform of the material's texture in the slot with the animated
+    /// offset, rotation and scale written over it, starting from the identity if it has none.
+    pub fn texture_transform(
+        &self,
+        index: usize,
+        slot: TextureSlot,
+        transform: Option<TextureTransformInfo>
+    ) -> Option<TextureTransformInfo> {
+        let value = |property: MaterialProperty| self.material_value(index, property)
+            .filter(|it| it.len() == property.component_count());
+        let offset = value(MaterialProperty::TextureOffset(slot));
+        let rotation = value(MaterialProperty::TextureRotation(slot));
+        let scale = value(MaterialProperty::TextureScale(slot));
+        if offset.is_none() && rotation.is_none() && scale.is_none() {
+            return transform;
+        }
+        let transform = transform.unwrap_or_else(TextureTransformInfo::identity);
+        Some(TextureTransformInfo::new(
+            offset.map(|it| Vector2::new(it[0], it[1])).unwrap_or(transform.offset()),
+            rotation.map(|it| it[0]).unwrap_or(transform.rotation()),
+            scale.map(|it| Vector2::new(it[0], it[1])).unwrap_or(transform.scale()),
+            transform.tex_coord()))
+    }
+
+    /// Writes the animated properties of the light over it.
+    pub fn apply_to_light(&self, index: usize, light: &mut LightInfo) {
+        for ((light_index, property), value) in &self.light_values {
+            if *light_index != index || value.len() != property.component_count() {
+                continue;
+            }
+            match property {
+                LightProperty::Color => light.set_color(Vector3::new(value[0], value[1], value[2])),
+                LightProperty::Intensity => light.set_intensity(value[0]),
+                LightProperty::Range => light.set_range(Some(value[0])),
+                LightProperty::InnerConeAngle => light.set_inner_cone_angle(value[0]),
+                LightProperty::OuterConeAngle => light.set_outer_cone_angle(value[0])
+            }
+        }
+    }
+
+    /// Returns the animated morph target weights of the node, None if they are not animated.
+    pub fn node_weights(&self, index: usize) -> Option<&Vec<GltfVecNum>> {
+        self.node_values.get(&(index, NodeProperty::Weights))
+    }
+
+    /// Writes the animated node transforms into the pose.
+    pub fn apply_to_pose(&self, pose: &mut NodePose) {
+        for ((index, property), value) in &self.node_values {
+            let transform = match pose.transforms_mut().get_mut(*index) {
+                Some(transform) => transform,
+                None => continue
+            };
+            match property {
+                NodeProperty::Translation if value.len() == 3 =>
+                    transform.set_translation(Vector3::new(value[0], value[1], value[2])),
+                NodeProperty::Rotation if value.len() == 4 =>
+                    transform.set_rotation(UnitQuaternion::from_quaternion(
+                        Quaternion::new(value[3], value[0], value[1], value[2]))),
+                NodeProperty::Scale if value.len() == 3 =>
+                    transform.set_scale(Vector3::new(value[0], value[1], value[2])),
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the rest pose of the glTF with the animated node transforms written in.
+    pub fn pose(&self, gltf: &LoadedGltf) -> NodePose {
+        let mut pose = NodePose::new_from_gltf(gltf);
+        self.apply_to_pose(&mut pose);
+        pose
+    }
+}
@@ -0,0 +1,118 @@
+extern crate image;
+
+use anyhow::{anyhow, Result};
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+use nalgebra::Vector3;
+use crate::util::image::{decode_image_levels, linear_to_srgb, srgb_to_linear};
+use super::{AnimationState, GltfVecNum, LoadedGltf, LoadedGltfMaterial};
+
+/// How much a fully metallic surface is darkened, since the vanilla shaders have no
+/// reflections for its colour to show up in.
+const METALLIC_DARKENING: GltfVecNum = 0.5;
+/// How much a fully metallic and fully smooth surface gets back as a faked specular sheen.
+const METALLIC_SHEEN: GltfVecNum = 0.25;
+
+/// Decodes the image the texture is sampled from, falling back to the core source of
+/// the texture if its extension source cannot be decoded.
+pub fn decode_texture_image(gltf: &LoadedGltf, texture_index: usize) -> Result<RgbaImage> {
+    let texture = gltf.textures().get(texture_index)
+        .ok_or_else(|| anyhow!("Texture index {} is out of bounds", texture_index))?;
+    let decode = |source_index: usize| -> Result<RgbaImage> {
+        let image = gltf.images().iter().find(|it| it.index() == source_index)
+            .ok_or_else(|| anyhow!("Image {} of texture {} is not loaded", source_index, texture_index))?;
+        decode_image_levels(image.data(), None)?.into_iter().next()
+            .ok_or_else(|| anyhow!("Image {} of texture {} has no pixels", source_index, texture_index))
+    };
+    let source_index = texture.source_index()
+        .ok_or_else(|| anyhow!("Texture {} has no source", texture_index))?;
+    decode(source_index).or_else(|err| match texture.fallback_source_index() {
+        Some(fallback_index) => decode(fallback_index),
+        None => Err(err)
+    })
+}
+
+/// Decodes the texture and scales it to the size, or returns None if there is no texture.
+fn decode_scaled(
+    gltf: &LoadedGltf,
+    texture_index: Option<usize>,
+    width: u32,
+    height: u32
+) -> Result<Option<RgbaImage>> {
+    let image = match texture_index {
+        Some(texture_index) => decode_texture_image(gltf, texture_index)?,
+        None => return Ok(None)
+    };
+    if image.width() == width && image.height() == height {
+        Ok(Some(image))
+    } else {
+        Ok(Some(image::imageops::resize(&image, width, height, FilterType::Triangle)))
+    }
+}
+
+fn channel(pixel: Option<&Rgba<u8>>, index: usize) -> GltfVecNum {
+    pixel.map(|it| it[index] as GltfVecNum / 255.0).unwrap_or(1.0)
+}
+
+/// Bakes the PBR maps of the material into the single albedo texture the vanilla entity
+/// shaders sample, at the resolution of the base colour texture (1x1 without one).
+///
+/// In linear space, the base colour (texture times factor) is darkened by the occlusion
+/// (scaled by its strength), then metals are darkened with a sheen added back the
+/// smoother they are, which is how they roughly read without reflections, and the
+/// emission (scaled by `KHR_materials_emissive_strength`) is added on top.
+///
+/// The maps are assumed to share the texture coordinates of the base colour texture;
+/// the other maps are scaled to its resolution. The factors are the ones of the
+/// animation state, if any.
+pub fn bake_albedo(
+    gltf: &LoadedGltf,
+    material: &LoadedGltfMaterial,
+    state: Option<&AnimationState>
+) -> Result<RgbaImage> {
+    let pbr = material.pbr_metallic_roughness();
+    let base_color = match pbr.base_color_texture() {
+        Some(texture) => Some(decode_texture_image(gltf, texture.index())?),
+        None => None
+    };
+    let (width, height) = base_color.as_ref().map(|it| it.dimensions()).unwrap_or((1, 1));
+    let metallic_roughness = decode_scaled(
+        gltf, pbr.metallic_roughness_texture().as_ref().map(|it| it.index()), width, height)?;
+    let occlusion = decode_scaled(
+        gltf, material.occlusion_texture().as_ref().map(|it| it.index()), width, height)?;
+    let emissive = decode_scaled(
+        gltf, material.emissive_texture().as_ref().map(|it| it.index()), width, height)?;
+
+    let factors = material.factors(state);
+    let base_color_factor = factors.base_color_factor;
+    let occlusion_strength = factors.occlusion_strength;
+    let emissive_factor = factors.emission();
+
+    Ok(RgbaImage::from_fn(width, height, |x, y| {
+        let base_pixel = base_color.as_ref().map(|it| it.get_pixel(x, y));
+        let mut color = Vector3::from_fn(|i, _| {
+            srgb_to_linear(channel(base_pixel, i)) * base_color_factor[i]
+        });
+        let alpha = channel(base_pixel, 3) * base_color_factor[3];
+
+        let occlusion_pixel = occlusion.as_ref().map(|it| it.get_pixel(x, y));
+        color *= 1.0 + occlusion_strength * (channel(occlusion_pixel, 0) - 1.0);
+
+        // Roughness is in the green channel and metalness in the blue one.
+        let metallic_roughness_pixel = metallic_roughness.as_ref().map(|it| it.get_pixel(x, y));
+        let metallic = (channel(metallic_roughness_pixel, 2) * factors.metallic_factor).clamp(0.0, 1.0);
+        let roughness = (channel(metallic_roughness_pixel, 1) * factors.roughness_factor).clamp(0.0, 1.0);
+        color *= 1.0 - metallic * METALLIC_DARKENING + metallic * (1.0 - roughness) * METALLIC_SHEEN;
+
+        let emissive_pixel = emissive.as_ref().map(|it| it.get_pixel(x, y));
+        color += Vector3::from_fn(|i, _| srgb_to_linear(channel(emissive_pixel, i)) * emissive_factor[i]);
+
+        let to_u8 = |it: GltfVecNum| (it.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Rgba([
+            to_u8(linear_to_srgb(color.x.clamp(0.0, 1.0))),
+            to_u8(linear_to_srgb(color.y.clamp(0.0, 1.0))),
+            to_u8(linear_to_srgb(color.z.clamp(0.0, 1.0))),
+            to_u8(alpha)
+        ])
+    }))
+}
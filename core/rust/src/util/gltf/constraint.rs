@@ -0,0 +1,363 @@
+extern crate nalgebra;
+
+use std::collections::HashMap;
+use anyhow::{bail, Result};
+use nalgebra::{UnitQuaternion, Vector3};
+use super::{GltfVecNum, LoadedGltf, NodePose};
+
+/// Where a look-at constraint makes its node look.
+#[derive(Clone, Copy)]
+pub enum LookAtTarget {
+    /// Yaw and pitch in degrees, as received by `EntityModel.setupAnim`.
+    /// Positive yaw turns towards +X, positive pitch looks down.
+    Angles(GltfVecNum, GltfVecNum),
+    /// A point in model space.
+    Point(Vector3<GltfVecNum>)
+}
+
+/// Where a two-bone IK constraint makes its chain reach, in model space.
+#[derive(Clone, Copy)]
+pub struct IkTarget {
+    pub target: Vector3<GltfVecNum>,
+    /// The point the mid joint bends towards.
+    pub pole: Vector3<GltfVecNum>
+}
+
+/// Rotates a node (usually the head) so that its +Z axis faces the target,
+/// with the yaw and pitch clamped into the given limits.
+pub struct LookAtConstraint {
+    node_index: usize,
+    /// (min, max) in degrees.
+    yaw_limits: (GltfVecNum, GltfVecNum),
+    /// (min, max) in degrees.
+    pitch_limits: (GltfVecNum, GltfVecNum)
+}
+
+/// An analytic two-bone IK solver for the limb chain root -> mid -> end,
+/// with the bending plane chosen by the pole target.
+pub struct TwoBoneIkConstraint {
+    root_index: usize,
+    mid_index: usize,
+    end_index: usize
+}
+
+pub enum Constraint {
+    LookAt(LookAtConstraint),
+    TwoBoneIk(TwoBoneIkConstraint)
+}
+
+/// The constraints of a glTF, applied in the order they were added.
+///
+/// The constraints belong to the model, while their targets belong to each instance
+/// of it (see `ConstraintTargets`).
+pub struct ConstraintSet {
+    constraints: Vec<Constraint>
+}
+
+/// The targets of the constraints of one glTF instance.
+#[derive(Clone)]
+pub struct ConstraintTargets {
+    /// The target of every look-at constraint.
+    look_at: LookAtTarget,
+    /// By the id of the two-bone IK constraint. A constraint without a target is disabled.
+    ik: HashMap<usize, IkTarget>
+}
+
+impl LookAtConstraint {
+    pub fn new(
+        node_index: usize,
+        yaw_limits: (GltfVecNum, GltfVecNum),
+        pitch_limits: (GltfVecNum, GltfVecNum)
+    ) -> Self {
+        Self {
+            node_index,
+            yaw_limits,
+            pitch_limits
+        }
+    }
+
+    pub fn node_index(&self) -> usize {
+        self.node_index
+    }
+
+    pub fn yaw_limits(&self) -> (GltfVecNum, GltfVecNum) {
+        self.yaw_limits
+    }
+
+    pub fn pitch_limits(&self) -> (GltfVecNum, GltfVecNum) {
+        self.pitch_limits
+    }
+
+    pub fn apply(&self, gltf: &LoadedGltf, pose: &mut NodePose, target: LookAtTarget) {
+        let (yaw, pitch) = match target {
+            LookAtTarget::Angles(yaw, pitch) => (yaw.to_radians(), pitch.to_radians()),
+            LookAtTarget::Point(point) => {
+                // The angles are measured in the space of the node's parent,
+                // as this is where the rotation is going to be applied.
+                let parent_rotation = match gltf.nodes()[self.node_index].parent_index() {
+                    Some(parent_index) => pose.world_rotation(gltf, parent_index),
+                    None => UnitQuaternion::identity()
+                };
+                let dir = parent_rotation.inverse()
+                    * (point - pose.world_position(gltf, self.node_index));
+                let horizontal = (dir.x * dir.x + dir.z * dir.z).sqrt();
+                (dir.x.atan2(dir.z), (-dir.y).atan2(horizontal))
+            }
+        };
+        let yaw = yaw.clamp(self.yaw_limits.0.to_radians(), self.yaw_limits.1.to_radians());
+        let pitch = pitch.clamp(self.pitch_limits.0.to_radians(), self.pitch_limits.1.to_radians());
+
+        let delta = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), yaw)
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), pitch);
+        let transform = &mut pose.transforms_mut()[self.node_index];
+        transform.set_rotation(delta * transform.rotation());
+    }
+}
+
+impl TwoBoneIkConstraint {
+    pub fn new(
+        root_index: usize,
+        mid_index: usize,
+        end_index: usize
+    ) -> Self {
+        Self {
+            root_index,
+            mid_index,
+            end_index
+        }
+    }
+
+    /// Fails unless the root is the parent of the mid node and the mid node the parent
+    /// of the end node, since the solver bends the chain at these joints.
+    pub fn new_from_chain(
+        gltf: &LoadedGltf,
+        root_index: usize,
+        mid_index: usize,
+        end_index: usize
+    ) -> Result<Self> {
+        let parent_of = |index: usize| gltf.nodes().get(index).and_then(|it| it.parent_index());
+        if parent_of(mid_index) != Some(root_index) || parent_of(end_index) != Some(mid_index) {
+            bail!("The nodes {} -> {} -> {} are not a chain of parents and children",
+                root_index, mid_index, end_index);
+        }
+        Ok(Self::new(root_index, mid_index, end_index))
+    }
+
+    pub fn root_index(&self) -> usize {
+        self.root_index
+    }
+
+    pub fn mid_index(&self) -> usize {
+        self.mid_index
+    }
+
+    pub fn end_index(&self) -> usize {
+        self.end_index
+    }
+
+    pub fn apply(&self, gltf: &LoadedGltf, pose: &mut NodePose, target: &IkTarget) {
+        let (target, pole) = (target.target, target.pole);
+        let a = pose.world_position(gltf, self.root_index);
+        let b = pose.world_position(gltf, self.mid_index);
+        let c = pose.world_position(gltf, self.end_index);
+        let len_ab = (b - a).norm();
+        let len_bc = (c - b).norm();
+        let to_target = target - a;
+        if len_ab <= GltfVecNum::EPSILON || len_bc <= GltfVecNum::EPSILON
+            || to_target.norm() <= GltfVecNum::EPSILON {
+            return;
+        }
+
+        // Keep the chain slightly bent when the target is out of reach,
+        // which avoids a degenerate bending plane.
+        let eps = 1e-4 * (len_ab + len_bc);
+        let len_at = to_target.norm().clamp((len_ab - len_bc).abs() + eps, len_ab + len_bc - eps);
+        let u = to_target.normalize();
+
+        // The bending direction is the pole projected onto the plane orthogonal
+        // to the root -> target axis, falling back to the current bending.
+        let bend = |v: Vector3<GltfVecNum>| {
+            let projected = v - u * v.dot(&u);
+            projected.try_normalize(GltfVecNum::EPSILON)
+        };
+        let v = match bend(pole - a).or_else(|| bend(b - a)) {
+            Some(v) => v,
+            None => return
+        };
+
+        // Law of cosines for the angle at the root.
+        let cos_root = ((len_ab * len_ab + len_at * len_at - len_bc * len_bc)
+            / (2.0 * len_ab * len_at)).clamp(-1.0, 1.0);
+        let sin_root = (1.0 - cos_root * cos_root).sqrt();
+        let b_new = a + (u * cos_root + v * sin_root) * len_ab;
+        let c_new = a + u * len_at;
+
+        let root_delta = UnitQuaternion::rotation_between(&(b - a), &(b_new - a))
+            .unwrap_or_else(UnitQuaternion::identity);
+        pose.rotate_in_world(gltf, self.root_index, root_delta);
+
+        let c_moved = pose.world_position(gltf, self.end_index);
+        let mid_delta = UnitQuaternion::rotation_between(&(c_moved - b_new), &(c_new - b_new))
+            .unwrap_or_else(UnitQuaternion::identity);
+        pose.rotate_in_world(gltf, self.mid_index, mid_delta);
+    }
+}
+
+impl Default for ConstraintSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConstraintSet {
+    pub fn new() -> Self {
+        Self {
+            constraints: Vec::new()
+        }
+    }
+
+    pub fn constraints(&self) -> &Vec<Constraint> {
+        &self.constraints
+    }
+
+    /// Returns the id of the added constraint, used to address it later on.
+    pub fn add(&mut self, constraint: Constraint) -> usize {
+        self.constraints.push(constraint);
+        self.constraints.len() - 1
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Constraint> {
+        self.constraints.get(id)
+    }
+
+    /// Post-processes a pose which has already been sampled from the animations
+    /// of an instance, with the constraint targets of the instance.
+    pub fn apply(&self, gltf: &LoadedGltf, pose: &mut NodePose, targets: &ConstraintTargets) {
+        for (id, constraint) in self.constraints.iter().enumerate() {
+            match constraint {
+                Constraint::LookAt(constraint) => constraint.apply(gltf, pose, targets.look_at()),
+                Constraint::TwoBoneIk(constraint) => if let Some(target) = targets.ik(id) {
+                    constraint.apply(gltf, pose, target)
+                }
+            }
+        }
+    }
+}
+
+impl Default for ConstraintTargets {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConstraintTargets {
+    /// Looks straight ahead, with every IK constraint disabled.
+    pub fn new() -> Self {
+        Self {
+            look_at: LookAtTarget::Angles(0.0, 0.0),
+            ik: HashMap::new()
+        }
+    }
+
+    pub fn look_at(&self) -> LookAtTarget {
+        self.look_at
+    }
+
+    /// Sets the target of every look-at constraint.
+    pub fn set_look_at(&mut self, target: LookAtTarget) {
+        self.look_at = target
+    }
+
+    /// Returns None if the IK constraint is disabled.
+    pub fn ik(&self, id: usize) -> Option<&IkTarget> {
+        self.ik.get(&id)
+    }
+
+    /// Sets the target of the IK constraint, None disabling it.
+    pub fn set_ik(&mut self, id: usize, target: Option<IkTarget>) {
+        match target {
+            Some(target) => self.ik.insert(id, target),
+            None => self.ik.remove(&id)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use super::*;
+    use crate::util::gltf::{parse_gltf, AnimationState, LoadedGltfNode};
+
+    const EPSILON: GltfVecNum = 1.0e-4;
+
+    /// A limb chain 0 -> 1 -> 2 pointing up, each bone a unit long, and a lone node 3.
+    fn load_limb() -> Arc<Mutex<LoadedGltf<'static>>> {
+        let gltf = parse_gltf(br#"{
+            "asset": { "version": "2.0" },
+            "nodes": [
+                { "children": [1] },
+                { "children": [2], "translation": [0.0, 1.0, 0.0] },
+                { "translation": [0.0, 1.0, 0.0] },
+                { "translation": [0.0, 0.0, 1.0] }
+            ]
+        }"#).unwrap();
+        let loaded = Arc::new(Mutex::new(LoadedGltf::new()));
+        let nodes: Vec<_> = gltf.nodes().map(|it| LoadedGltfNode::new_from_node(&loaded, &it)).collect();
+        {
+            let mut loaded_gltf = loaded.lock().unwrap();
+            *loaded_gltf.nodes_mut() = nodes;
+            loaded_gltf.link_node_parents();
+        }
+        loaded
+    }
+
+    #[test]
+    fn ik_chains_have_to_be_parents_and_children() {
+        let loaded = load_limb();
+        let gltf = loaded.lock().unwrap();
+        assert!(TwoBoneIkConstraint::new_from_chain(&gltf, 0, 1, 2).is_ok());
+        assert!(TwoBoneIkConstraint::new_from_chain(&gltf, 0, 2, 1).is_err());
+        assert!(TwoBoneIkConstraint::new_from_chain(&gltf, 0, 1, 3).is_err());
+        assert!(TwoBoneIkConstraint::new_from_chain(&gltf, 3, 0, 1).is_err());
+        assert!(TwoBoneIkConstraint::new_from_chain(&gltf, 0, 1, 9).is_err());
+    }
+
+    #[test]
+    fn look_at_targets_belong_to_each_instance() {
+        let loaded = load_limb();
+        let mut gltf = loaded.lock().unwrap();
+        gltf.constraints_mut().add(Constraint::LookAt(LookAtConstraint::new(0, (-90.0, 90.0), (-90.0, 90.0))));
+
+        let still = AnimationState::new();
+        let mut turned = AnimationState::new();
+        turned.constraint_targets_mut().set_look_at(LookAtTarget::Angles(0.0, 90.0));
+
+        // Pitching the root a quarter turn swings the chain from +Y over to +Z.
+        let still_position = gltf.evaluate_pose(&still).world_position(&gltf, 2);
+        let turned_position = gltf.evaluate_pose(&turned).world_position(&gltf, 2);
+        assert!((still_position - Vector3::new(0.0, 2.0, 0.0)).norm() < EPSILON, "{}", still_position);
+        assert!((turned_position - Vector3::new(0.0, 0.0, 2.0)).norm() < EPSILON, "{}", turned_position);
+    }
+
+    #[test]
+    fn ik_targets_belong_to_each_instance() {
+        let loaded = load_limb();
+        let mut gltf = loaded.lock().unwrap();
+        let chain = TwoBoneIkConstraint::new_from_chain(&gltf, 0, 1, 2).unwrap();
+        let id = gltf.constraints_mut().add(Constraint::TwoBoneIk(chain));
+
+        let rest = AnimationState::new();
+        let mut reaching = AnimationState::new();
+        let target = Vector3::new(1.0, 1.0, 0.0);
+        reaching.constraint_targets_mut().set_ik(id, Some(IkTarget { target, pole: Vector3::new(0.0, 2.0, 0.0) }));
+
+        let rest_end = gltf.evaluate_pose(&rest).world_position(&gltf, 2);
+        let reaching_end = gltf.evaluate_pose(&reaching).world_position(&gltf, 2);
+        assert!((rest_end - Vector3::new(0.0, 2.0, 0.0)).norm() < EPSILON, "{}", rest_end);
+        assert!((reaching_end - target).norm() < EPSILON, "{}", reaching_end);
+
+        reaching.constraint_targets_mut().set_ik(id, None);
+        let released_end = gltf.evaluate_pose(&reaching).world_position(&gltf, 2);
+        assert!((released_end - rest_end).norm() < EPSILON, "{}", released_end);
+    }
+}
@@ -0,0 +1,74 @@
+extern crate nalgebra;
+
+use nalgebra::Matrix4;
+use super::{GltfVecNum, LoadedGltf, NodePose};
+
+/// One mesh to draw, placed by its world matrix.
+pub struct DrawItem {
+    node_index: usize,
+    mesh_index: usize,
+    /// The `EXT_mesh_gpu_instancing` instance the item stands for, if the
+    /// instances are expanded.
+    instance_index: Option<usize>,
+    world_matrix: Matrix4<GltfVecNum>
+}
+
+impl DrawItem {
+    pub fn new(
+        node_index: usize,
+        mesh_index: usize,
+        instance_index: Option<usize>,
+        world_matrix: Matrix4<GltfVecNum>
+    ) -> Self {
+        Self {
+            node_index,
+            mesh_index,
+            instance_index,
+            world_matrix
+        }
+    }
+
+    pub fn node_index(&self) -> usize {
+        self.node_index
+    }
+
+    pub fn mesh_index(&self) -> usize {
+        self.mesh_index
+    }
+
+    pub fn instance_index(&self) -> Option<usize> {
+        self.instance_index
+    }
+
+    pub fn world_matrix(&self) -> Matrix4<GltfVecNum> {
+        self.world_matrix
+    }
+}
+
+/// Traverses the node hierarchy depth-first from the root nodes of the default
+/// scene and collects a draw item for every node with a mesh.
+///
+/// If `expand_instances` is true, instanced nodes give one draw item per instance,
+/// for renderers that cannot instance. Otherwise they give a single item, the
+/// instance transforms being available through `LoadedGltfNode::instances`.
+pub fn collect_draw_items(gltf: &LoadedGltf, pose: &NodePose, expand_instances: bool) -> Vec<DrawItem> {
+    let world_matrices = pose.world_matrices(gltf);
+    let mut result = Vec::new();
+    let mut stack: Vec<usize> = gltf.root_nodes();
+    stack.reverse();
+    while let Some(index) = stack.pop() {
+        let node = &gltf.nodes()[index];
+        if let Some(mesh_index) = node.mesh_index() {
+            if expand_instances && !node.instances().is_empty() {
+                node.instances().iter().enumerate().for_each(|(instance_index, it)| {
+                    result.push(DrawItem::new(
+                        index, mesh_index, Some(instance_index), world_matrices[index] * it.matrix()));
+                });
+            } else {
+                result.push(DrawItem::new(index, mesh_index, None, world_matrices[index]));
+            }
+        }
+        stack.extend(node.children().iter().rev().filter(|it| **it < gltf.nodes().len()));
+    }
+    result
+}
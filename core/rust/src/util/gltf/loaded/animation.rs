@@ -0,0 +1,652 @@
+extern crate gltf;
+extern crate nalgebra;
+
+use anyhow::{anyhow, bail, Result};
+use gltf::animation::{Interpolation, Property};
+use gltf::json::Value;
+use nalgebra::{Quaternion, UnitQuaternion};
+use super::LoadedGltf;
+use super::material::{TextureSlot, TEXTURE_TRANSFORM_EXTENSION};
+use super::light::LIGHTS_PUNCTUAL_EXTENSION;
+use super::material_extension::{EmissiveStrength, MaterialExtensionData};
+use crate::util::gltf::{extras_to_string, GltfVecNum};
+
+pub const ANIMATION_POINTER_EXTENSION: &str = "KHR_animation_pointer";
+
+/// The node properties animation channels can target.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NodeProperty {
+    Translation,
+    Rotation,
+    Scale,
+    Weights
+}
+
+/// The material properties `KHR_animation_pointer` channels can target.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MaterialProperty {
+    BaseColorFactor,
+    MetallicFactor,
+    RoughnessFactor,
+    EmissiveFactor,
+    EmissiveStrength,
+    AlphaCutoff,
+    NormalScale,
+    OcclusionStrength,
+    TextureOffset(TextureSlot),
+    TextureRotation(TextureSlot),
+    TextureScale(TextureSlot)
+}
+
+/// The light properties `KHR_animation_pointer` channels can target.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LightProperty {
+    Color,
+    Intensity,
+    Range,
+    InnerConeAngle,
+    OuterConeAngle
+}
+
+/// What an animation channel writes its sampled values into.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AnimationTarget {
+    Node(usize, NodeProperty),
+    Material(usize, MaterialProperty),
+    Light(usize, LightProperty)
+}
+
+/// The keyframes of an animation sampler, read out of its accessors.
+pub struct AnimationSamplerInfo {
+    /// The keyframe times in seconds, ascending.
+    input: Vec<GltfVecNum>,
+    /// The components of the keyframe values, keyframe after keyframe. With cubic
+    /// spline interpolation, each keyframe holds an in-tangent, a value and an out-tangent.
+    output: Vec<GltfVecNum>,
+    interpolation: Interpolation
+}
+
+pub struct AnimationChannelInfo {
+    sampler_index: usize,
+    target: AnimationTarget
+}
+
+pub struct AnimationInfo {
+    /// None if not defined in glTF.
+    name: Option<String>,
+    samplers: Vec<AnimationSamplerInfo>,
+    channels: Vec<AnimationChannelInfo>,
+    /// The `extras` of the animation as JSON text. None if not defined in glTF.
+    extras: Option<String>
+}
+
+impl NodeProperty {
+    pub fn from_property(property: Property) -> Self {
+        match property {
+            Property::Translation => Self::Translation,
+            Property::Rotation => Self::Rotation,
+            Property::Scale => Self::Scale,
+            Property::MorphTargetWeights => Self::Weights
+        }
+    }
+}
+
+impl MaterialProperty {
+    /// The number of components of the property's values.
+    pub fn component_count(&self) -> usize {
+        match self {
+            Self::BaseColorFactor => 4,
+            Self::EmissiveFactor => 3,
+            Self::TextureOffset(_) | Self::TextureScale(_) => 2,
+            _ => 1
+        }
+    }
+}
+
+impl LightProperty {
+    /// The number of components of the property's values.
+    pub fn component_count(&self) -> usize {
+        match self {
+            Self::Color => 3,
+            _ => 1
+        }
+    }
+}
+
+impl AnimationTarget {
+    /// Resolves a `KHR_animation_pointer` JSON pointer against the glTF.
+    ///
+    /// Returns an error if the pointer is malformed or points at an object the glTF
+    /// does not have, and None if it is valid but points at a property which
+    /// cannot be animated here (so that the channel is ignored, as the extension requires).
+    pub fn new_from_pointer(pointer: &str, gltf: &LoadedGltf) -> Result<Option<Self>> {
+        let segments = match pointer.strip_prefix('/') {
+            Some(pointer) => pointer.split('/')
+                .map(|it| it.replace("~1", "/").replace("~0", "~"))
+                .collect::<Vec<_>>(),
+            None => bail!("The animation pointer {} does not start with '/'", pointer)
+        };
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+        let index_of = |segment: &str, count: usize| -> Result<usize> {
+            let index = segment.parse::<usize>()
+                .map_err(|_| anyhow!("The animation pointer {} has an invalid index {}", pointer, segment))?;
+            if index >= count {
+                bail!("The animation pointer {} points out of range", pointer);
+            }
+            Ok(index)
+        };
+
+        let target = match segments.as_slice() {
+            ["nodes", index, property] => {
+                let index = index_of(index, gltf.nodes().len())?;
+                let property = match *property {
+                    "translation" => NodeProperty::Translation,
+                    "rotation" => NodeProperty::Rotation,
+                    "scale" => NodeProperty::Scale,
+                    "weights" => NodeProperty::Weights,
+                    _ => return Ok(None)
+                };
+                Self::Node(index, property)
+            }
+            ["materials", index, rest @ ..] => {
+                let index = index_of(index, gltf.materials().len())?;
+                let texture_slot = |texture: &[&str]| match texture {
+                    ["pbrMetallicRoughness", "baseColorTexture"] => Some(TextureSlot::BaseColor),
+                    ["pbrMetallicRoughness", "metallicRoughnessTexture"] => Some(TextureSlot::MetallicRoughness),
+                    ["normalTexture"] => Some(TextureSlot::Normal),
+                    ["occlusionTexture"] => Some(TextureSlot::Occlusion),
+                    ["emissiveTexture"] => Some(TextureSlot::Emissive),
+                    _ => None
+                };
+                let property = match rest {
+                    ["pbrMetallicRoughness", "baseColorFactor"] => MaterialProperty::BaseColorFactor,
+                    ["pbrMetallicRoughness", "metallicFactor"] => MaterialProperty::MetallicFactor,
+                    ["pbrMetallicRoughness", "roughnessFactor"] => MaterialProperty::RoughnessFactor,
+                    ["emissiveFactor"] => MaterialProperty::EmissiveFactor,
+                    ["alphaCutoff"] => MaterialProperty::AlphaCutoff,
+                    ["normalTexture", "scale"] => MaterialProperty::NormalScale,
+                    ["occlusionTexture", "strength"] => MaterialProperty::OcclusionStrength,
+                    ["extensions", extension, "emissiveStrength"] if *extension == EmissiveStrength::NAME =>
+                        MaterialProperty::EmissiveStrength,
+                    [texture @ .., "extensions", extension, field] if *extension == TEXTURE_TRANSFORM_EXTENSION => {
+                        let slot = match texture_slot(texture) {
+                            Some(slot) => slot,
+                            None => return Ok(None)
+                        };
+                        match *field {
+                            "offset" => MaterialProperty::TextureOffset(slot),
+                            "rotation" => MaterialProperty::TextureRotation(slot),
+                            "scale" => MaterialProperty::TextureScale(slot),
+                            _ => return Ok(None)
+                        }
+                    }
+                    _ => return Ok(None)
+                };
+                Self::Material(index, property)
+            }
+            ["extensions", extension, "lights", index, rest @ ..] if *extension == LIGHTS_PUNCTUAL_EXTENSION => {
+                let index = index_of(index, gltf.lights().len())?;
+                let property = match rest {
+                    ["color"] => LightProperty::Color,
+                    ["intensity"] => LightProperty::Intensity,
+                    ["range"] => LightProperty::Range,
+                    ["spot", "innerConeAngle"] => LightProperty::InnerConeAngle,
+                    ["spot", "outerConeAngle"] => LightProperty::OuterConeAngle,
+                    _ => return Ok(None)
+                };
+                Self::Light(index, property)
+            }
+            _ => return Ok(None)
+        };
+        Ok(Some(target))
+    }
+
+    /// The number of components of the target's values, None for morph target
+    /// weights, whose count depends on the mesh.
+    pub fn component_count(&self) -> Option<usize> {
+        match self {
+            Self::Node(_, NodeProperty::Rotation) => Some(4),
+            Self::Node(_, NodeProperty::Weights) => None,
+            Self::Node(_, _) => Some(3),
+            Self::Material(_, property) => Some(property.component_count()),
+            Self::Light(_, property) => Some(property.component_count())
+        }
+    }
+}
+
+impl AnimationSamplerInfo {
+    pub fn new(
+        input: Vec<GltfVecNum>,
+        output: Vec<GltfVecNum>,
+        interpolation: Interpolation
+    ) -> Self {
+        Self {
+            input,
+            output,
+            interpolation
+        }
+    }
+
+    pub fn new_from_sampler(sampler: &gltf::animation::Sampler, gltf: &LoadedGltf) -> Result<Self> {
+        let read = |index: usize| gltf.accessors().get(index)
+            .map(|it| it.read_components(gltf))
+            .ok_or_else(|| anyhow!("Accessor {} of animation sampler {} is not loaded", index, sampler.index()));
+        Ok(Self::new(
+            read(sampler.input().index())?,
+            read(sampler.output().index())?,
+            sampler.interpolation()))
+    }
+
+    pub fn input(&self) -> &Vec<GltfVecNum> {
+        &self.input
+    }
+
+    pub fn output(&self) -> &Vec<GltfVecNum> {
+        &self.output
+    }
+
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Returns the time of the last keyframe.
+    pub fn duration(&self) -> GltfVecNum {
+        self.input.last().copied().unwrap_or(0.0)
+    }
+
+    /// The number of components per keyframe value.
+    pub fn component_count(&self) -> usize {
+        let values_per_keyframe = match self.interpolation {
+            Interpolation::CubicSpline => 3,
+            _ => 1
+        };
+        match self.input.len() * values_per_keyframe {
+            0 => 0,
+            it => self.output.len() / it
+        }
+    }
+
+    /// Samples the value at the time, clamped into the keyframe range.
+    /// Quaternions are interpolated spherically if `rotation` is true.
+    pub fn sample(&self, time: GltfVecNum, rotation: bool) -> Vec<GltfVecNum> {
+        let width = self.component_count();
+        if width == 0 {
+            return Vec::new();
+        }
+        let keyframe = |index: usize, part: usize| -> &[GltfVecNum] {
+            let at = match self.interpolation {
+                Interpolation::CubicSpline => (index * 3 + part) * width,
+                _ => index * width
+            };
+            &self.output[at..at + width]
+        };
+        // The value itself is the middle of a cubic spline keyframe.
+        let value_of = |index: usize| keyframe(index, 1);
+
+        let next = self.input.partition_point(|it| *it <= time);
+        if next == 0 {
+            return value_of(0).to_vec();
+        }
+        if next == self.input.len() {
+            return value_of(next - 1).to_vec();
+        }
+        let previous = next - 1;
+        let delta = self.input[next] - self.input[previous];
+        let t = if delta > 0.0 { (time - self.input[previous]) / delta } else { 0.0 };
+
+        let result: Vec<GltfVecNum> = match self.interpolation {
+            Interpolation::Step => return value_of(previous).to_vec(),
+            Interpolation::Linear if rotation && width == 4 => {
+                let quaternion = |it: &[GltfVecNum]| UnitQuaternion::from_quaternion(
+                    Quaternion::new(it[3], it[0], it[1], it[2]));
+                let result = quaternion(value_of(previous)).slerp(&quaternion(value_of(next)), t);
+                return vec![result.i, result.j, result.k, result.w];
+            }
+            Interpolation::Linear => value_of(previous).iter().zip(value_of(next))
+                .map(|(a, b)| a + (b - a) * t)
+                .collect(),
+            Interpolation::CubicSpline => {
+                let (t2, t3) = (t * t, t * t * t);
+                let out_tangent = keyframe(previous, 2);
+                let in_tangent = keyframe(next, 0);
+                (0..width).map(|i| {
+                    (2.0 * t3 - 3.0 * t2 + 1.0) * value_of(previous)[i]
+                        + (t3 - 2.0 * t2 + t) * delta * out_tangent[i]
+                        + (-2.0 * t3 + 3.0 * t2) * value_of(next)[i]
+                        + (t3 - t2) * delta * in_tangent[i]
+                }).collect()
+            }
+        };
+        if rotation && width == 4 {
+            let norm = result.iter().map(|it| it * it).sum::<GltfVecNum>().sqrt();
+            if norm > 0.0 {
+                return result.iter().map(|it| it / norm).collect();
+            }
+        }
+        result
+    }
+}
+
+impl AnimationChannelInfo {
+    pub fn new(sampler_index: usize, target: AnimationTarget) -> Self {
+        Self {
+            sampler_index,
+            target
+        }
+    }
+
+    pub fn sampler_index(&self) -> usize {
+        self.sampler_index
+    }
+
+    pub fn target(&self) -> AnimationTarget {
+        self.target
+    }
+}
+
+impl AnimationInfo {
+    /// Loads the samplers and the channels of the animation, the `KHR_animation_pointer`
+    /// channels included (see `move_pointer_channels`).
+    pub fn new_from_animation(animation: &gltf::Animation, gltf: &LoadedGltf) -> Result<Self> {
+        let samplers = animation.samplers()
+            .map(|it| AnimationSamplerInfo::new_from_sampler(&it, gltf))
+            .collect::<Result<Vec<_>>>()?;
+        let mut channels: Vec<AnimationChannelInfo> = animation.channels()
+            .map(|it| AnimationChannelInfo::new(
+                it.sampler().index(),
+                AnimationTarget::Node(it.target().node().index(), NodeProperty::from_property(it.target().property()))))
+            .collect();
+
+        let pointer_channels = animation.extension_value(ANIMATION_POINTER_EXTENSION)
+            .and_then(|it| it.get("channels"))
+            .and_then(Value::as_array);
+        for channel in pointer_channels.into_iter().flatten() {
+            let sampler_index = channel.get("sampler").and_then(Value::as_u64)
+                .map(|it| it as usize)
+                .filter(|it| *it < samplers.len())
+                .ok_or_else(|| anyhow!("An animation pointer channel has no valid sampler"))?;
+            let pointer = channel.get("target")
+                .and_then(|it| it.get("extensions"))
+                .and_then(|it| it.get(ANIMATION_POINTER_EXTENSION))
+                .and_then(|it| it.get("pointer"))
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("An animation pointer channel has no pointer"))?;
+            let target = match AnimationTarget::new_from_pointer(pointer, gltf)? {
+                Some(target) => target,
+                None => continue
+            };
+            let sampler_width = samplers[sampler_index].component_count();
+            if let Some(width) = target.component_count() {
+                if width != sampler_width {
+                    bail!("The animation pointer {} takes {} components, but its sampler gives {}",
+                        pointer, width, sampler_width);
+                }
+            }
+            channels.push(AnimationChannelInfo::new(sampler_index, target));
+        }
+
+        Ok(Self {
+            name: animation.name().map(String::from),
+            samplers,
+            channels,
+            extras: extras_to_string(animation.extras())
+        })
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn samplers(&self) -> &Vec<AnimationSamplerInfo> {
+        &self.samplers
+    }
+
+    pub fn channels(&self) -> &Vec<AnimationChannelInfo> {
+        &self.channels
+    }
+
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    /// Returns the time of the last keyframe over all samplers.
+    pub fn duration(&self) -> GltfVecNum {
+        self.samplers.iter().map(|it| it.duration()).fold(0.0, GltfVecNum::max)
+    }
+
+    /// Samples every channel at the time, returning the target with its value.
+    pub fn sample(&self, time: GltfVecNum) -> Vec<(AnimationTarget, Vec<GltfVecNum>)> {
+        self.channels.iter().map(|it| {
+            let rotation = matches!(it.target, AnimationTarget::Node(_, NodeProperty::Rotation));
+            (it.target, self.samplers[it.sampler_index].sample(time, rotation))
+        }).collect()
+    }
+}
+
+/// Moves the `KHR_animation_pointer` channels of the animations of a glTF JSON out of
+/// their `channels`, since the gltf crate rejects channels without a target node, into
+/// `extensions.KHR_animation_pointer.channels` of their animation, where
+/// `AnimationInfo::new_from_animation` picks them up.
+pub fn move_pointer_channels(json: &mut Value) {
+    let animations = match json.get_mut("animations").and_then(Value::as_array_mut) {
+        Some(animations) => animations,
+        None => return
+    };
+    for animation in animations {
+        let channels = match animation.get_mut("channels").and_then(Value::as_array_mut) {
+            Some(channels) => channels,
+            None => continue
+        };
+        let (pointer_channels, node_channels): (Vec<Value>, Vec<Value>) = channels.drain(..)
+            .partition(|it| it.get("target")
+                .and_then(|it| it.get("path"))
+                .and_then(Value::as_str) == Some("pointer"));
+        *channels = node_channels;
+        if pointer_channels.is_empty() {
+            continue;
+        }
+        let mut extension = Value::Object(Default::default());
+        extension["channels"] = Value::Array(pointer_channels);
+        if animation.get("extensions").is_none() {
+            animation["extensions"] = Value::Object(Default::default());
+        }
+        animation["extensions"][ANIMATION_POINTER_EXTENSION] = extension;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use gltf::json::Value;
+    use super::*;
+    use crate::util::gltf::loaded::{LoadedGltfMaterial, LoadedGltfNode};
+    use crate::util::gltf::loaded::light::read_lights;
+    use crate::util::gltf::parse_gltf;
+
+    const EPSILON: GltfVecNum = 1.0e-5;
+
+    fn assert_close(actual: &[GltfVecNum], expected: &[GltfVecNum]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} != {:?}", actual, expected);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < EPSILON, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    /// Loads the nodes, materials and lights of the glTF JSON, which is all pointers resolve against.
+    fn load(json: &str) -> Arc<Mutex<LoadedGltf<'static>>> {
+        let gltf = parse_gltf(json.as_bytes()).unwrap();
+        let loaded = Arc::new(Mutex::new(LoadedGltf::new()));
+        let nodes: Vec<_> = gltf.nodes().map(|it| LoadedGltfNode::new_from_node(&loaded, &it)).collect();
+        let materials: Vec<_> = gltf.materials().map(|it| LoadedGltfMaterial::new_from_material(&loaded, &it)).collect();
+        let json: Value = gltf::json::deserialize::from_slice(json.as_bytes()).unwrap();
+        let lights = read_lights(&json["extensions"][LIGHTS_PUNCTUAL_EXTENSION]).unwrap();
+        {
+            let mut gltf = loaded.lock().unwrap();
+            *gltf.nodes_mut() = nodes;
+            *gltf.materials_mut() = materials;
+            *gltf.lights_mut() = lights;
+        }
+        loaded
+    }
+
+    fn resolve(pointer: &str) -> Result<Option<AnimationTarget>> {
+        let loaded = load(r#"{
+            "asset": { "version": "2.0" },
+            "nodes": [ {}, {} ],
+            "materials": [ {} ],
+            "extensions": {
+                "KHR_lights_punctual": { "lights": [ { "type": "spot" } ] }
+            }
+        }"#);
+        let gltf = loaded.lock().unwrap();
+        AnimationTarget::new_from_pointer(pointer, &gltf)
+    }
+
+    #[test]
+    fn step_sampling_holds_the_previous_keyframe() {
+        let sampler = AnimationSamplerInfo::new(vec![0.0, 1.0], vec![1.0, 2.0], Interpolation::Step);
+        assert_close(&sampler.sample(0.0, false), &[1.0]);
+        assert_close(&sampler.sample(0.99, false), &[1.0]);
+        assert_close(&sampler.sample(1.0, false), &[2.0]);
+    }
+
+    #[test]
+    fn linear_sampling_interpolates_each_component() {
+        let sampler = AnimationSamplerInfo::new(
+            vec![0.0, 2.0], vec![0.0, 10.0, -4.0, 2.0, 20.0, 4.0], Interpolation::Linear);
+        assert_eq!(sampler.component_count(), 3);
+        assert_close(&sampler.sample(0.5, false), &[0.5, 12.5, -2.0]);
+    }
+
+    #[test]
+    fn linear_rotation_sampling_slerps() {
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        // From the identity to a quarter turn about Z, in xyzw order.
+        let sampler = AnimationSamplerInfo::new(
+            vec![0.0, 1.0], vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, half, half], Interpolation::Linear);
+        let eighth = std::f32::consts::FRAC_PI_8;
+        assert_close(&sampler.sample(0.5, true), &[0.0, 0.0, eighth.sin(), eighth.cos()]);
+    }
+
+    #[test]
+    fn cubic_spline_sampling_uses_the_tangents() {
+        // Keyframes of in-tangent, value and out-tangent.
+        let flat = AnimationSamplerInfo::new(
+            vec![0.0, 1.0], vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0], Interpolation::CubicSpline);
+        assert_eq!(flat.component_count(), 1);
+        assert_close(&flat.sample(0.5, false), &[0.5]);
+        assert_close(&flat.sample(0.25, false), &[0.15625]);
+
+        // With a unit slope on both ends, the spline is the straight line.
+        let sloped = AnimationSamplerInfo::new(
+            vec![0.0, 2.0], vec![0.0, 0.0, 0.5, 0.5, 1.0, 0.0], Interpolation::CubicSpline);
+        assert_close(&sloped.sample(0.5, false), &[0.25]);
+    }
+
+    #[test]
+    fn cubic_spline_rotation_sampling_is_normalized() {
+        let sampler = AnimationSamplerInfo::new(
+            vec![0.0, 1.0],
+            vec![
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0,
+                0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0
+            ],
+            Interpolation::CubicSpline);
+        let result = sampler.sample(0.5, true);
+        let norm = result.iter().map(|it| it * it).sum::<GltfVecNum>().sqrt();
+        assert!((norm - 1.0).abs() < EPSILON);
+        assert_close(&result, &[0.0, 0.0, 0.5f32.sqrt(), 0.5f32.sqrt()]);
+    }
+
+    #[test]
+    fn sampling_clamps_to_the_keyframe_range() {
+        let sampler = AnimationSamplerInfo::new(vec![1.0, 2.0], vec![3.0, 5.0], Interpolation::Linear);
+        assert_close(&sampler.sample(-1.0, false), &[3.0]);
+        assert_close(&sampler.sample(10.0, false), &[5.0]);
+        assert_eq!(sampler.duration(), 2.0);
+
+        let empty = AnimationSamplerInfo::new(Vec::new(), Vec::new(), Interpolation::Linear);
+        assert!(empty.sample(0.0, false).is_empty());
+    }
+
+    #[test]
+    fn pointers_resolve_to_node_properties() {
+        assert_eq!(resolve("/nodes/1/rotation").unwrap(), Some(AnimationTarget::Node(1, NodeProperty::Rotation)));
+        assert_eq!(resolve("/nodes/0/weights").unwrap(), Some(AnimationTarget::Node(0, NodeProperty::Weights)));
+    }
+
+    #[test]
+    fn pointers_resolve_to_material_properties() {
+        assert_eq!(
+            resolve("/materials/0/pbrMetallicRoughness/baseColorFactor").unwrap(),
+            Some(AnimationTarget::Material(0, MaterialProperty::BaseColorFactor)));
+        assert_eq!(
+            resolve("/materials/0/extensions/KHR_materials_emissive_strength/emissiveStrength").unwrap(),
+            Some(AnimationTarget::Material(0, MaterialProperty::EmissiveStrength)));
+        assert_eq!(
+            resolve("/materials/0/pbrMetallicRoughness/baseColorTexture/extensions/KHR_texture_transform/offset")
+                .unwrap(),
+            Some(AnimationTarget::Material(0, MaterialProperty::TextureOffset(TextureSlot::BaseColor))));
+        assert_eq!(
+            resolve("/materials/0/normalTexture/extensions/KHR_texture_transform/rotation").unwrap(),
+            Some(AnimationTarget::Material(0, MaterialProperty::TextureRotation(TextureSlot::Normal))));
+    }
+
+    #[test]
+    fn pointers_resolve_to_light_properties() {
+        assert_eq!(
+            resolve("/extensions/KHR_lights_punctual/lights/0/color").unwrap(),
+            Some(AnimationTarget::Light(0, LightProperty::Color)));
+        assert_eq!(
+            resolve("/extensions/KHR_lights_punctual/lights/0/spot/outerConeAngle").unwrap(),
+            Some(AnimationTarget::Light(0, LightProperty::OuterConeAngle)));
+    }
+
+    #[test]
+    fn unsupported_pointers_resolve_to_none() {
+        assert_eq!(resolve("/nodes/0/matrix").unwrap(), None);
+        assert_eq!(resolve("/materials/0/doubleSided").unwrap(), None);
+        assert_eq!(resolve("/materials/0/unknownTexture/extensions/KHR_texture_transform/offset").unwrap(), None);
+        assert_eq!(resolve("/cameras/0/perspective/yfov").unwrap(), None);
+    }
+
+    #[test]
+    fn malformed_pointers_fail() {
+        assert!(resolve("nodes/0/rotation").is_err());
+        assert!(resolve("/nodes/first/rotation").is_err());
+        assert!(resolve("/nodes/2/rotation").is_err());
+        assert!(resolve("/materials/1/alphaCutoff").is_err());
+        assert!(resolve("/extensions/KHR_lights_punctual/lights/1/intensity").is_err());
+    }
+
+    #[test]
+    fn pointer_channels_are_moved_into_the_extension() {
+        let mut json: Value = gltf::json::deserialize::from_str(r#"{
+            "animations": [
+                {
+                    "channels": [
+                        { "sampler": 0, "target": { "node": 0, "path": "rotation" } },
+                        { "sampler": 1, "target": { "path": "pointer" } }
+                    ],
+                    "extensions": { "EXT_other": {} }
+                },
+                {
+                    "channels": [ { "sampler": 0, "target": { "node": 0, "path": "scale" } } ]
+                }
+            ]
+        }"#).unwrap();
+        move_pointer_channels(&mut json);
+
+        let first = &json["animations"][0];
+        assert_eq!(first["channels"].as_array().unwrap().len(), 1);
+        assert_eq!(first["channels"][0]["target"]["path"], "rotation");
+        let moved = first["extensions"][ANIMATION_POINTER_EXTENSION]["channels"].as_array().unwrap();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0]["sampler"], 1);
+        assert!(first["extensions"].get("EXT_other").is_some());
+
+        let second = &json["animations"][1];
+        assert_eq!(second["channels"].as_array().unwrap().len(), 1);
+        assert!(second.get("extensions").is_none());
+    }
+}
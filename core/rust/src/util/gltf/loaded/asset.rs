@@ -0,0 +1,107 @@
+extern crate gltf;
+
+use crate::util::gltf::{extras_to_string, is_extension_supported};
+
+/// The kinds of glTF objects which keep their `extras`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ObjectKind {
+    Buffer,
+    BufferView,
+    Accessor,
+    Image,
+    Sampler,
+    Texture,
+    Material,
+    Mesh,
+    Node,
+    Animation,
+    Light
+}
+
+/// The root-level metadata of a glTF: its `asset` block, its extension lists and its `extras`.
+#[derive(Clone, Default)]
+pub struct AssetInfo {
+    /// None if not defined in glTF.
+    generator: Option<String>,
+    version: String,
+    /// None if not defined in glTF.
+    copyright: Option<String>,
+    /// None if not defined in glTF.
+    min_version: Option<String>,
+    extensions_used: Vec<String>,
+    extensions_required: Vec<String>,
+    /// The root `extras` as JSON text. None if not defined in glTF.
+    extras: Option<String>
+}
+
+impl ObjectKind {
+    /// The ids match the ones of the Kotlin `GltfObjectKind` enum.
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(Self::Buffer),
+            1 => Some(Self::BufferView),
+            2 => Some(Self::Accessor),
+            3 => Some(Self::Image),
+            4 => Some(Self::Sampler),
+            5 => Some(Self::Texture),
+            6 => Some(Self::Material),
+            7 => Some(Self::Mesh),
+            8 => Some(Self::Node),
+            9 => Some(Self::Animation),
+            10 => Some(Self::Light),
+            _ => None
+        }
+    }
+}
+
+impl AssetInfo {
+    pub fn new_from_document(document: &gltf::Document) -> Self {
+        let root = document.as_json();
+        Self {
+            generator: root.asset.generator.clone(),
+            version: root.asset.version.clone(),
+            copyright: root.asset.copyright.clone(),
+            min_version: root.asset.min_version.clone(),
+            extensions_used: document.extensions_used().map(String::from).collect(),
+            extensions_required: document.extensions_required().map(String::from).collect(),
+            extras: extras_to_string(&root.extras)
+        }
+    }
+
+    pub fn generator(&self) -> Option<&String> {
+        self.generator.as_ref()
+    }
+
+    pub fn version(&self) -> &String {
+        &self.version
+    }
+
+    pub fn copyright(&self) -> Option<&String> {
+        self.copyright.as_ref()
+    }
+
+    pub fn min_version(&self) -> Option<&String> {
+        self.min_version.as_ref()
+    }
+
+    pub fn extensions_used(&self) -> &Vec<String> {
+        &self.extensions_used
+    }
+
+    pub fn extensions_required(&self) -> &Vec<String> {
+        &self.extensions_required
+    }
+
+    /// Returns the extensions the glTF uses without requiring them which the loader
+    /// does not support, and which are therefore ignored.
+    pub fn ignored_extensions(&self) -> Vec<String> {
+        self.extensions_used.iter()
+            .filter(|it| !is_extension_supported(it))
+            .cloned()
+            .collect()
+    }
+
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+}
@@ -0,0 +1,175 @@
+extern crate gltf;
+extern crate meshopt;
+
+use std::os::raw::c_void;
+use anyhow::{anyhow, Result};
+use gltf::json::Value;
+use super::LoadedGltf;
+
+pub const MESHOPT_COMPRESSION_EXTENSION: &str = "EXT_meshopt_compression";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MeshoptCompressionMode {
+    Attributes,
+    Triangles,
+    Indices
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MeshoptCompressionFilter {
+    None,
+    Octahedral,
+    Quaternion,
+    Exponential
+}
+
+/// `EXT_meshopt_compression` of a buffer view, which points at the compressed
+/// bytes in another buffer than the (fallback) one of the view itself.
+#[derive(Clone, Copy, Debug)]
+pub struct MeshoptCompressionInfo {
+    buffer_index: usize,
+    data_offset: usize,
+    data_length: usize,
+    data_stride: usize,
+    count: usize,
+    mode: MeshoptCompressionMode,
+    filter: MeshoptCompressionFilter
+}
+
+impl MeshoptCompressionMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ATTRIBUTES" => Some(Self::Attributes),
+            "TRIANGLES" => Some(Self::Triangles),
+            "INDICES" => Some(Self::Indices),
+            _ => None
+        }
+    }
+}
+
+impl MeshoptCompressionFilter {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "NONE" => Some(Self::None),
+            "OCTAHEDRAL" => Some(Self::Octahedral),
+            "QUATERNION" => Some(Self::Quaternion),
+            "EXPONENTIAL" => Some(Self::Exponential),
+            _ => None
+        }
+    }
+}
+
+impl MeshoptCompressionInfo {
+    pub fn new_from_json(json: &Value) -> Result<Self> {
+        let usize_of = |key: &str| json.get(key).and_then(Value::as_u64).map(|it| it as usize);
+        let mode_name = json.get("mode").and_then(Value::as_str).unwrap_or("");
+        let filter_name = json.get("filter").and_then(Value::as_str).unwrap_or("NONE");
+        Ok(Self {
+            buffer_index: usize_of("buffer")
+                .ok_or_else(|| anyhow!("Missing buffer of {}", MESHOPT_COMPRESSION_EXTENSION))?,
+            data_offset: usize_of("byteOffset").unwrap_or(0),
+            data_length: usize_of("byteLength")
+                .ok_or_else(|| anyhow!("Missing byteLength of {}", MESHOPT_COMPRESSION_EXTENSION))?,
+            data_stride: usize_of("byteStride")
+                .ok_or_else(|| anyhow!("Missing byteStride of {}", MESHOPT_COMPRESSION_EXTENSION))?,
+            count: usize_of("count")
+                .ok_or_else(|| anyhow!("Missing count of {}", MESHOPT_COMPRESSION_EXTENSION))?,
+            mode: MeshoptCompressionMode::from_name(mode_name)
+                .ok_or_else(|| anyhow!("Unknown meshopt compression mode: {}", mode_name))?,
+            filter: MeshoptCompressionFilter::from_name(filter_name)
+                .ok_or_else(|| anyhow!("Unknown meshopt compression filter: {}", filter_name))?
+        })
+    }
+
+    pub fn buffer_index(&self) -> usize {
+        self.buffer_index
+    }
+
+    pub fn data_offset(&self) -> usize {
+        self.data_offset
+    }
+
+    pub fn data_length(&self) -> usize {
+        self.data_length
+    }
+
+    pub fn data_stride(&self) -> usize {
+        self.data_stride
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mode(&self) -> MeshoptCompressionMode {
+        self.mode
+    }
+
+    pub fn filter(&self) -> MeshoptCompressionFilter {
+        self.filter
+    }
+
+    /// Decodes the compressed bytes into the `count * byteStride` bytes of the
+    /// buffer view, the filter being applied after the attributes are decoded.
+    pub fn decode(&self, gltf: &LoadedGltf) -> Result<Vec<u8>> {
+        let buffer = gltf.buffers().get(self.buffer_index)
+            .ok_or_else(|| anyhow!("Buffer {} of the compressed buffer view is not loaded", self.buffer_index))?;
+        let source = buffer.data().get(self.data_offset..self.data_offset + self.data_length)
+            .ok_or_else(|| anyhow!("The compressed data exceeds buffer {}", self.buffer_index))?;
+
+        match self.mode {
+            MeshoptCompressionMode::Attributes => {
+                if self.data_stride == 0 || !self.data_stride.is_multiple_of(4) || self.data_stride > 256 {
+                    return Err(anyhow!("Invalid byteStride of meshopt attributes: {}", self.data_stride));
+                }
+            }
+            MeshoptCompressionMode::Triangles | MeshoptCompressionMode::Indices => {
+                if self.data_stride != 2 && self.data_stride != 4 {
+                    return Err(anyhow!("Invalid byteStride of meshopt indices: {}", self.data_stride));
+                }
+            }
+        }
+        let filter_stride_valid = match self.filter {
+            MeshoptCompressionFilter::None => true,
+            _ if self.mode != MeshoptCompressionMode::Attributes => false,
+            MeshoptCompressionFilter::Octahedral => self.data_stride == 4 || self.data_stride == 8,
+            MeshoptCompressionFilter::Quaternion => self.data_stride == 8,
+            MeshoptCompressionFilter::Exponential => true
+        };
+        if !filter_stride_valid {
+            return Err(anyhow!("Meshopt filter {:?} is not allowed with mode {:?} and byteStride {}",
+                self.filter, self.mode, self.data_stride));
+        }
+
+        let mut result = vec![0u8; self.count * self.data_stride];
+        let dest = result.as_mut_ptr() as *mut c_void;
+        // SAFETY: the destination holds exactly count * byteStride bytes, which
+        // is what the decoders write, and the source slice is read only.
+        let code = unsafe {
+            match self.mode {
+                MeshoptCompressionMode::Attributes => meshopt::ffi::meshopt_decodeVertexBuffer(
+                    dest, self.count, self.data_stride, source.as_ptr(), source.len()),
+                MeshoptCompressionMode::Triangles => meshopt::ffi::meshopt_decodeIndexBuffer(
+                    dest, self.count, self.data_stride, source.as_ptr(), source.len()),
+                MeshoptCompressionMode::Indices => meshopt::ffi::meshopt_decodeIndexSequence(
+                    dest, self.count, self.data_stride, source.as_ptr(), source.len())
+            }
+        };
+        if code != 0 {
+            return Err(anyhow!("Failed to decode meshopt compressed data, error code: {}", code));
+        }
+
+        unsafe {
+            match self.filter {
+                MeshoptCompressionFilter::None => {}
+                MeshoptCompressionFilter::Octahedral => meshopt::ffi::meshopt_decodeFilterOct(
+                    dest, self.count, self.data_stride),
+                MeshoptCompressionFilter::Quaternion => meshopt::ffi::meshopt_decodeFilterQuat(
+                    dest, self.count, self.data_stride),
+                MeshoptCompressionFilter::Exponential => meshopt::ffi::meshopt_decodeFilterExp(
+                    dest, self.count, self.data_stride)
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,134 @@
+extern crate draco_core;
+extern crate gltf;
+
+use anyhow::{anyhow, Result};
+use draco_core::{DecoderBuffer, Mesh, MeshDecoder, PointAttribute};
+use gltf::accessor::DataType;
+use gltf::json::Value;
+use super::LoadedGltf;
+
+pub const DRACO_MESH_COMPRESSION_EXTENSION: &str = "KHR_draco_mesh_compression";
+
+/// `KHR_draco_mesh_compression` of a primitive.
+pub struct DracoCompressionInfo {
+    buffer_view_index: usize,
+    /// The semantic names of the attributes, with the unique ids of the
+    /// corresponding attributes in the Draco mesh.
+    attributes: Vec<(String, u32)>
+}
+
+impl DracoCompressionInfo {
+    pub fn new_from_json(json: &Value) -> Result<Self> {
+        let buffer_view_index = json.get("bufferView").and_then(Value::as_u64)
+            .ok_or_else(|| anyhow!("Missing bufferView of {}", DRACO_MESH_COMPRESSION_EXTENSION))?;
+        let attributes = match json.get("attributes").and_then(Value::as_object) {
+            Some(attributes) => attributes.iter()
+                .filter_map(|(name, id)| Some((name.clone(), id.as_u64()? as u32)))
+                .collect(),
+            None => Vec::new()
+        };
+        Ok(Self {
+            buffer_view_index: buffer_view_index as usize,
+            attributes
+        })
+    }
+
+    pub fn buffer_view_index(&self) -> usize {
+        self.buffer_view_index
+    }
+
+    pub fn attributes(&self) -> &Vec<(String, u32)> {
+        &self.attributes
+    }
+
+    pub fn decode_mesh(&self, gltf: &LoadedGltf) -> Result<Mesh> {
+        let buffer_view = gltf.buffer_views().get(self.buffer_view_index)
+            .ok_or_else(|| anyhow!("Buffer view {} of the Draco mesh is not loaded", self.buffer_view_index))?;
+        let mut buffer = DecoderBuffer::new(buffer_view.data_slice(gltf));
+        let mut mesh = Mesh::new();
+        MeshDecoder::new().decode(&mut buffer, &mut mesh)
+            .map_err(|err| anyhow!("Failed to decode the Draco mesh: {}", err))?;
+        Ok(mesh)
+    }
+
+    /// Decodes the mesh and writes its attributes and indices into the placeholder
+    /// accessors of the primitive, in the component types those accessors declare,
+    /// so that they are read as if the primitive had never been compressed.
+    pub fn decode_into_accessors(&self, gltf: &mut LoadedGltf, primitive: &gltf::mesh::Primitive) -> Result<()> {
+        let mesh = self.decode_mesh(gltf)?;
+        let mut decoded: Vec<(usize, Vec<u8>)> = Vec::new();
+
+        for (semantic, accessor) in primitive.attributes() {
+            let name = semantic.to_string();
+            let unique_id = match self.attributes.iter().find(|it| it.0 == name) {
+                Some((_, unique_id)) => *unique_id,
+                // Not compressed, so the accessor already has its own data.
+                None => continue
+            };
+            let attribute = mesh.attribute_by_unique_id(unique_id)
+                .ok_or_else(|| anyhow!("The Draco mesh has no attribute {} for {}", unique_id, name))?;
+            let multiplicity = accessor.dimensions().multiplicity();
+            let values = attribute.read_f32s(mesh.num_points(), multiplicity);
+            let scale = draco_normalization_scale(attribute);
+            let mut data = vec![0u8; accessor.count() * accessor.size()];
+            let comp_bytes = accessor.data_type().size();
+            data.chunks_exact_mut(comp_bytes).zip(values.iter()).for_each(|(bytes, value)| {
+                write_component(bytes, *value / scale, accessor.data_type(), accessor.normalized());
+            });
+            decoded.push((accessor.index(), data));
+        }
+
+        if let Some(accessor) = primitive.indices() {
+            let comp_bytes = accessor.data_type().size();
+            let mut data = vec![0u8; accessor.count() * comp_bytes];
+            let indices = mesh.faces().iter().flat_map(|face| face.iter().map(|it| it.0));
+            data.chunks_exact_mut(comp_bytes).zip(indices).for_each(|(bytes, index)| {
+                match accessor.data_type() {
+                    DataType::U8 => bytes[0] = index as u8,
+                    DataType::U16 => bytes.copy_from_slice(&(index as u16).to_le_bytes()),
+                    _ => bytes.copy_from_slice(&index.to_le_bytes())
+                }
+            });
+            decoded.push((accessor.index(), data));
+        }
+
+        decoded.into_iter().for_each(|(index, data)| {
+            if let Some(accessor) = gltf.accessors_mut().get_mut(index) {
+                accessor.set_decoded_data(Some(data));
+            }
+        });
+        Ok(())
+    }
+}
+
+/// The divisor turning the values of a normalised integer Draco attribute into [0, 1] or [-1, 1].
+fn draco_normalization_scale(attribute: &PointAttribute) -> f32 {
+    if !attribute.normalized() {
+        return 1.0;
+    }
+    match attribute.data_type() {
+        draco_core::DataType::Int8 => i8::MAX as f32,
+        draco_core::DataType::Uint8 => u8::MAX as f32,
+        draco_core::DataType::Int16 => i16::MAX as f32,
+        draco_core::DataType::Uint16 => u16::MAX as f32,
+        draco_core::DataType::Int32 => i32::MAX as f32,
+        draco_core::DataType::Uint32 => u32::MAX as f32,
+        _ => 1.0
+    }
+}
+
+/// Writes one little-endian accessor component, the inverse of the glTF normalisation rules
+/// being applied to normalised integers.
+fn write_component(bytes: &mut [u8], value: f32, data_type: DataType, normalized: bool) {
+    let quantize = |max: f32| if normalized { (value * max).round() } else { value.round() };
+    match data_type {
+        DataType::I8 => bytes[0] = quantize(i8::MAX as f32).clamp(i8::MIN as f32, i8::MAX as f32) as i8 as u8,
+        DataType::U8 => bytes[0] = quantize(u8::MAX as f32).clamp(0.0, u8::MAX as f32) as u8,
+        DataType::I16 => bytes.copy_from_slice(
+            &(quantize(i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16).to_le_bytes()),
+        DataType::U16 => bytes.copy_from_slice(
+            &(quantize(u16::MAX as f32).clamp(0.0, u16::MAX as f32) as u16).to_le_bytes()),
+        DataType::U32 => bytes.copy_from_slice(&(value.round().max(0.0) as u32).to_le_bytes()),
+        DataType::F32 => bytes.copy_from_slice(&value.to_le_bytes())
+    }
+}
@@ -0,0 +1,199 @@
+extern crate gltf;
+extern crate nalgebra;
+
+use std::f32::consts::FRAC_PI_4;
+use anyhow::{anyhow, Result};
+use gltf::json::Value;
+use nalgebra::Vector3;
+use crate::util::gltf::GltfVecNum;
+
+/// The name of the punctual lights extension, both at the root and on nodes.
+pub const LIGHTS_PUNCTUAL_EXTENSION: &str = "KHR_lights_punctual";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LightType {
+    Directional,
+    Point,
+    Spot
+}
+
+/// One light of `KHR_lights_punctual`. Lights shine along the -Z axis of their node.
+#[derive(Clone)]
+pub struct LightInfo {
+    /// None if not defined in glTF.
+    name: Option<String>,
+    light_type: LightType,
+    /// Linear RGB.
+    color: Vector3<GltfVecNum>,
+    /// In candela (lm/sr) for point and spot lights, in lux (lm/m^2) for directional ones.
+    intensity: GltfVecNum,
+    /// None if the range is infinite.
+    range: Option<GltfVecNum>,
+    /// In radians. Only meaningful for spot lights.
+    inner_cone_angle: GltfVecNum,
+    /// In radians. Only meaningful for spot lights.
+    outer_cone_angle: GltfVecNum,
+    /// The `extras` of the light as JSON text. None if not defined in glTF.
+    extras: Option<String>
+}
+
+impl LightType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "directional" => Some(Self::Directional),
+            "point" => Some(Self::Point),
+            "spot" => Some(Self::Spot),
+            _ => None
+        }
+    }
+
+    pub fn id(&self) -> i32 {
+        match self {
+            Self::Directional => 0,
+            Self::Point => 1,
+            Self::Spot => 2
+        }
+    }
+}
+
+impl LightInfo {
+    /// Returns None if the light has no valid type.
+    pub fn new_from_json(json: &Value) -> Option<Self> {
+        let f32_of = |json: &Value, key: &str, default: GltfVecNum| json.get(key)
+            .and_then(Value::as_f64).map(|it| it as GltfVecNum).unwrap_or(default);
+        let light_type = LightType::from_name(json.get("type")?.as_str()?)?;
+        let color = match json.get("color").and_then(Value::as_array) {
+            Some(arr) if arr.len() == 3 => Vector3::from_fn(|i, _| {
+                arr[i].as_f64().map(|it| it as GltfVecNum).unwrap_or(1.0)
+            }),
+            _ => Vector3::new(1.0, 1.0, 1.0)
+        };
+        let spot = json.get("spot").cloned().unwrap_or(Value::Null);
+        Some(Self {
+            name: json.get("name").and_then(Value::as_str).map(String::from),
+            light_type,
+            color,
+            intensity: f32_of(json, "intensity", 1.0),
+            range: json.get("range").and_then(Value::as_f64).map(|it| it as GltfVecNum),
+            inner_cone_angle: f32_of(&spot, "innerConeAngle", 0.0),
+            outer_cone_angle: f32_of(&spot, "outerConeAngle", FRAC_PI_4),
+            extras: json.get("extras").map(Value::to_string)
+        })
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn light_type(&self) -> LightType {
+        self.light_type
+    }
+
+    pub fn color(&self) -> Vector3<GltfVecNum> {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Vector3<GltfVecNum>) {
+        self.color = color
+    }
+
+    pub fn intensity(&self) -> GltfVecNum {
+        self.intensity
+    }
+
+    pub fn set_intensity(&mut self, intensity: GltfVecNum) {
+        self.intensity = intensity
+    }
+
+    /// Returns None if the range is infinite.
+    pub fn range(&self) -> Option<GltfVecNum> {
+        self.range
+    }
+
+    pub fn set_range(&mut self, range: Option<GltfVecNum>) {
+        self.range = range
+    }
+
+    pub fn inner_cone_angle(&self) -> GltfVecNum {
+        self.inner_cone_angle
+    }
+
+    pub fn set_inner_cone_angle(&mut self, inner_cone_angle: GltfVecNum) {
+        self.inner_cone_angle = inner_cone_angle
+    }
+
+    pub fn outer_cone_angle(&self) -> GltfVecNum {
+        self.outer_cone_angle
+    }
+
+    pub fn set_outer_cone_angle(&mut self, outer_cone_angle: GltfVecNum) {
+        self.outer_cone_angle = outer_cone_angle
+    }
+
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+}
+
+/// Returns the index of the light the node references, if any.
+pub fn node_light_index(node: &gltf::Node) -> Option<usize> {
+    node.extension_value(LIGHTS_PUNCTUAL_EXTENSION)
+        .and_then(|it| it.get("light"))
+        .and_then(Value::as_u64)
+        .map(|it| it as usize)
+}
+
+/// Reads the lights of the root `KHR_lights_punctual` extension, by light index.
+///
+/// A light without a valid type fails the whole read rather than being skipped,
+/// since the lights after it would otherwise shift down the indices nodes and
+/// animation pointers reference them by.
+pub fn read_lights(extension: &Value) -> Result<Vec<LightInfo>> {
+    match extension.get("lights").and_then(Value::as_array) {
+        Some(lights) => lights.iter().enumerate()
+            .map(|(index, it)| LightInfo::new_from_json(it)
+                .ok_or_else(|| anyhow!("Failed to load glTF light {}: the light type is invalid", index)))
+            .collect(),
+        None => Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lights_are_read_by_index() {
+        let extension: Value = gltf::json::deserialize::from_str(r#"{
+            "lights": [
+                { "type": "point", "name": "first" },
+                { "type": "spot", "name": "second", "spot": { "outerConeAngle": 0.5 } },
+                { "type": "directional", "name": "third", "intensity": 3.0 }
+            ]
+        }"#).unwrap();
+        let lights = read_lights(&extension).unwrap();
+        let names: Vec<Option<String>> = lights.iter().map(LightInfo::name).collect();
+        assert_eq!(names, vec![Some("first".to_string()), Some("second".to_string()), Some("third".to_string())]);
+        assert_eq!(lights[1].light_type(), LightType::Spot);
+        assert_eq!(lights[1].outer_cone_angle(), 0.5);
+        assert_eq!(lights[2].intensity(), 3.0);
+    }
+
+    #[test]
+    fn an_invalid_light_fails_the_read_instead_of_shifting_the_others() {
+        let extension: Value = gltf::json::deserialize::from_str(r#"{
+            "lights": [
+                { "type": "point" },
+                { "type": "area" },
+                { "type": "spot" }
+            ]
+        }"#).unwrap();
+        let err = read_lights(&extension).err().unwrap();
+        assert!(err.to_string().contains("light 1"), "{}", err);
+    }
+
+    #[test]
+    fn no_lights_read_as_empty() {
+        assert!(read_lights(&Value::Object(Default::default())).unwrap().is_empty());
+    }
+}
@@ -1,11 +1,114 @@
+extern crate gltf;
 extern crate nalgebra;
 
 use crate::util::gltf::GltfVecNum;
-use nalgebra::{SVector, Vector4};
+use gltf::json::Value;
+use nalgebra::{Matrix3, SVector, Vector2, Vector4};
+
+/// The `KHR_texture_transform` extension of a texture reference.
+#[derive(Clone, Copy)]
+pub struct TextureTransformInfo {
+    offset: Vector2<GltfVecNum>,
+    /// In radians, counter-clockwise.
+    rotation: GltfVecNum,
+    scale: Vector2<GltfVecNum>,
+    /// None if the texture reference's own texCoord is not overridden.
+    tex_coord: Option<usize>
+}
+
+/// How the renderer should light the primitives of a material.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ShadingHint {
+    /// Regular diffuse lighting.
+    Lit,
+    /// No diffuse lighting, only the vanilla entity lighting (light map and overlay).
+    VanillaEntityLighting,
+    /// No lighting at all.
+    FullBright
+}
+
+/// The kind of Minecraft `RenderType` the primitives of a material need.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RenderCategory {
+    /// Opaque and back-face culled, like `entitySolid`.
+    Solid,
+    /// Alpha-tested and back-face culled, like `entityCutout`.
+    Cutout,
+    /// Alpha-tested or opaque, without culling, like `entityCutoutNoCull`.
+    CutoutNoCull,
+    /// Alpha-blended, like `entityTranslucent`.
+    Translucent,
+    /// Glowing surfaces which do not depend on the lighting, like `eyes`.
+    Emissive,
+    /// Unlit blended glow which adds to what is behind it, like `energySwirl`.
+    Additive
+}
+
+/// Everything the renderer needs to pick the `RenderType` of a primitive.
+#[derive(Clone, Copy)]
+pub struct RenderClass {
+    category: RenderCategory,
+    shading_hint: ShadingHint,
+    /// The texture to bind. None if the material has no texture for the category.
+    texture_index: Option<usize>,
+    /// Whether the emission of a lit material has to be drawn as a second, emissive layer.
+    emissive_layer: bool,
+    /// The texture of the emissive layer. None if the emission is a plain colour.
+    emissive_texture_index: Option<usize>
+}
+
+/// The factors of a material `KHR_animation_pointer` channels can animate, with the values
+/// an animation state wrote over them.
+#[derive(Clone, Copy)]
+pub struct MaterialFactors {
+    pub base_color_factor: SVector<GltfVecNum, 4>,
+    pub metallic_factor: GltfVecNum,
+    pub roughness_factor: GltfVecNum,
+    pub emissive_factor: EmissiveFactorInfo,
+    /// The `KHR_materials_emissive_strength` of the material, 1 without the extension.
+    pub emissive_strength: GltfVecNum,
+    pub alpha_cutoff: GltfVecNum,
+    /// 1 without a normal texture.
+    pub normal_scale: GltfVecNum,
+    /// 1 without an occlusion texture.
+    pub occlusion_strength: GltfVecNum
+}
+
+/// How the materials sample an image, which decides how its mipmaps are generated.
+#[derive(Clone, Copy)]
+pub struct ImageUsage {
+    /// Whether the image holds sRGB colours (base colour or emission) rather than linear data.
+    color: bool,
+    /// The alpha cutoff of a `MASK` material taking its base colour from the image.
+    /// None if no such material exists.
+    alpha_cutoff: Option<GltfVecNum>
+}
+
+/// The texture references a material can have.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TextureSlot {
+    BaseColor,
+    MetallicRoughness,
+    Normal,
+    Occlusion,
+    Emissive
+}
+
+/// What any texture reference of a material resolves to, whatever its slot.
+#[derive(Clone, Copy)]
+pub struct TextureReference {
+    texture_index: usize,
+    /// With the texture transform's override taken into account.
+    tex_coord: usize,
+    /// None if not defined in glTF.
+    texture_transform: Option<TextureTransformInfo>
+}
 
 pub struct PbrMetallicRoughnessTextureInfo {
     index: usize,
-    tex_coord: usize
+    tex_coord: usize,
+    /// None if not defined in glTF.
+    texture_transform: Option<TextureTransformInfo>
 }
 
 pub struct PbrMetallicRoughnessInfo {
@@ -19,15 +122,28 @@ pub struct PbrMetallicRoughnessInfo {
 pub struct NormalTextureInfo {
     scale: GltfVecNum,
     index: usize,
-    tex_coord: usize
+    tex_coord: usize,
+    /// None if not defined in glTF.
+    texture_transform: Option<TextureTransformInfo>
 }
 
 pub struct OcclusionTextureInfo {
     strength: GltfVecNum,
     index: usize,
-    tex_coord: usize
+    tex_coord: usize,
+    /// None if not defined in glTF.
+    texture_transform: Option<TextureTransformInfo>
 }
 
+/// The alpha cutoff the glTF specification defines for materials not giving one.
+pub const DEFAULT_ALPHA_CUTOFF: GltfVecNum = 0.5;
+
+/// The name of the texture transform extension within texture references.
+pub const TEXTURE_TRANSFORM_EXTENSION: &str = "KHR_texture_transform";
+
+/// The name of the unlit material extension.
+pub const UNLIT_EXTENSION: &str = "KHR_materials_unlit";
+
 pub type EmissiveTextureInfo = PbrMetallicRoughnessTextureInfo;
 
 pub type EmissiveFactorInfo = SVector<GltfVecNum, 3>;
@@ -39,7 +155,8 @@ impl PbrMetallicRoughnessTextureInfo {
     ) -> Self {
         Self {
             index,
-            tex_coord
+            tex_coord,
+            texture_transform: None
         }
     }
 
@@ -50,6 +167,29 @@ impl PbrMetallicRoughnessTextureInfo {
     pub fn tex_coord(&self) -> usize {
         self.tex_coord
     }
+
+    pub fn texture_transform(&self) -> Option<TextureTransformInfo> {
+        self.texture_transform
+    }
+
+    pub fn set_texture_transform(&mut self, texture_transform: Option<TextureTransformInfo>) {
+        self.texture_transform = texture_transform
+    }
+
+    /// Returns the texCoord set to sample with, taking the texture transform's
+    /// override into account.
+    pub fn effective_tex_coord(&self) -> usize {
+        effective_tex_coord(self.tex_coord, &self.texture_transform)
+    }
+
+    pub fn reference(&self) -> TextureReference {
+        TextureReference::new(self.index, self.effective_tex_coord(), self.texture_transform)
+    }
+
+    /// Returns the UV matrix of the texture transform, identity if there is none.
+    pub fn uv_matrix(&self) -> Matrix3<GltfVecNum> {
+        uv_matrix(&self.texture_transform)
+    }
 }
 
 impl PbrMetallicRoughnessInfo {
@@ -124,7 +264,8 @@ impl NormalTextureInfo {
         Self {
             scale,
             index,
-            tex_coord
+            tex_coord,
+            texture_transform: None
         }
     }
 
@@ -140,6 +281,29 @@ impl NormalTextureInfo {
     pub fn tex_coord(&self) -> usize {
         self.tex_coord
     }
+
+    pub fn texture_transform(&self) -> Option<TextureTransformInfo> {
+        self.texture_transform
+    }
+
+    pub fn set_texture_transform(&mut self, texture_transform: Option<TextureTransformInfo>) {
+        self.texture_transform = texture_transform
+    }
+
+    /// Returns the texCoord set to sample with, taking the texture transform's
+    /// override into account.
+    pub fn effective_tex_coord(&self) -> usize {
+        effective_tex_coord(self.tex_coord, &self.texture_transform)
+    }
+
+    pub fn reference(&self) -> TextureReference {
+        TextureReference::new(self.index, self.effective_tex_coord(), self.texture_transform)
+    }
+
+    /// Returns the UV matrix of the texture transform, identity if there is none.
+    pub fn uv_matrix(&self) -> Matrix3<GltfVecNum> {
+        uv_matrix(&self.texture_transform)
+    }
 }
 
 impl OcclusionTextureInfo {
@@ -151,7 +315,8 @@ impl OcclusionTextureInfo {
         Self {
             strength,
             index,
-            tex_coord
+            tex_coord,
+            texture_transform: None
         }
     }
 
@@ -167,4 +332,264 @@ impl OcclusionTextureInfo {
     pub fn tex_coord(&self) -> usize {
         self.tex_coord
     }
+
+    pub fn texture_transform(&self) -> Option<TextureTransformInfo> {
+        self.texture_transform
+    }
+
+    pub fn set_texture_transform(&mut self, texture_transform: Option<TextureTransformInfo>) {
+        self.texture_transform = texture_transform
+    }
+
+    /// Returns the texCoord set to sample with, taking the texture transform's
+    /// override into account.
+    pub fn effective_tex_coord(&self) -> usize {
+        effective_tex_coord(self.tex_coord, &self.texture_transform)
+    }
+
+    pub fn reference(&self) -> TextureReference {
+        TextureReference::new(self.index, self.effective_tex_coord(), self.texture_transform)
+    }
+
+    /// Returns the UV matrix of the texture transform, identity if there is none.
+    pub fn uv_matrix(&self) -> Matrix3<GltfVecNum> {
+        uv_matrix(&self.texture_transform)
+    }
+}
+
+impl TextureTransformInfo {
+    pub fn new(
+        offset: Vector2<GltfVecNum>,
+        rotation: GltfVecNum,
+        scale: Vector2<GltfVecNum>,
+        tex_coord: Option<usize>
+    ) -> Self {
+        Self {
+            offset,
+            rotation,
+            scale,
+            tex_coord
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(Vector2::zeros(), 0.0, Vector2::new(1.0, 1.0), None)
+    }
+
+    /// Parses the JSON of the extension, filling in the defaults of the
+    /// specification for the properties not given.
+    pub fn new_from_json(json: &Value) -> Self {
+        let vec2_of = |key: &str, default: Vector2<GltfVecNum>| {
+            match json.get(key).and_then(Value::as_array) {
+                Some(arr) if arr.len() == 2 => Vector2::new(
+                    arr[0].as_f64().unwrap_or(default.x as f64) as GltfVecNum,
+                    arr[1].as_f64().unwrap_or(default.y as f64) as GltfVecNum),
+                _ => default
+            }
+        };
+        let offset = vec2_of("offset", Vector2::zeros());
+        let scale = vec2_of("scale", Vector2::new(1.0, 1.0));
+        let rotation = json.get("rotation").and_then(Value::as_f64).unwrap_or(0.0) as GltfVecNum;
+        let tex_coord = json.get("texCoord").and_then(Value::as_u64).map(|it| it as usize);
+        Self::new(offset, rotation, scale, tex_coord)
+    }
+
+    pub fn offset(&self) -> Vector2<GltfVecNum> {
+        self.offset
+    }
+
+    pub fn rotation(&self) -> GltfVecNum {
+        self.rotation
+    }
+
+    pub fn scale(&self) -> Vector2<GltfVecNum> {
+        self.scale
+    }
+
+    pub fn tex_coord(&self) -> Option<usize> {
+        self.tex_coord
+    }
+
+    /// Returns the UV matrix, which is translation * rotation * scale
+    /// as defined by the extension.
+    pub fn matrix(&self) -> Matrix3<GltfVecNum> {
+        let (sin, cos) = self.rotation.sin_cos();
+        let translation = Matrix3::new(
+            1.0, 0.0, self.offset.x,
+            0.0, 1.0, self.offset.y,
+            0.0, 0.0, 1.0);
+        let rotation = Matrix3::new(
+            cos, sin, 0.0,
+            -sin, cos, 0.0,
+            0.0, 0.0, 1.0);
+        let scale = Matrix3::new(
+            self.scale.x, 0.0, 0.0,
+            0.0, self.scale.y, 0.0,
+            0.0, 0.0, 1.0);
+        translation * rotation * scale
+    }
+
+    pub fn apply(&self, uv: Vector2<GltfVecNum>) -> Vector2<GltfVecNum> {
+        let result = self.matrix() * uv.push(1.0);
+        Vector2::new(result.x, result.y)
+    }
+}
+
+fn effective_tex_coord(tex_coord: usize, texture_transform: &Option<TextureTransformInfo>) -> usize {
+    texture_transform.and_then(|it| it.tex_coord).unwrap_or(tex_coord)
+}
+
+fn uv_matrix(texture_transform: &Option<TextureTransformInfo>) -> Matrix3<GltfVecNum> {
+    texture_transform.map(|it| it.matrix()).unwrap_or_else(Matrix3::identity)
+}
+
+impl TextureSlot {
+    /// The ids match the ones of the Kotlin `GltfTextureSlot` enum.
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(Self::BaseColor),
+            1 => Some(Self::MetallicRoughness),
+            2 => Some(Self::Normal),
+            3 => Some(Self::Occlusion),
+            4 => Some(Self::Emissive),
+            _ => None
+        }
+    }
+}
+
+impl TextureReference {
+    pub fn new(
+        texture_index: usize,
+        tex_coord: usize,
+        texture_transform: Option<TextureTransformInfo>
+    ) -> Self {
+        Self {
+            texture_index,
+            tex_coord,
+            texture_transform
+        }
+    }
+
+    /// Parses a textureInfo JSON object, as found within material extensions.
+    /// Returns None if it has no texture index.
+    pub fn new_from_json(json: &Value) -> Option<Self> {
+        let texture_index = json.get("index")?.as_u64()? as usize;
+        let tex_coord = json.get("texCoord").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let texture_transform = json.get("extensions")
+            .and_then(|it| it.get(TEXTURE_TRANSFORM_EXTENSION))
+            .map(TextureTransformInfo::new_from_json);
+        Some(Self::new(
+            texture_index,
+            effective_tex_coord(tex_coord, &texture_transform),
+            texture_transform))
+    }
+
+    pub fn texture_index(&self) -> usize {
+        self.texture_index
+    }
+
+    pub fn tex_coord(&self) -> usize {
+        self.tex_coord
+    }
+
+    pub fn texture_transform(&self) -> Option<TextureTransformInfo> {
+        self.texture_transform
+    }
+
+    pub fn uv_matrix(&self) -> Matrix3<GltfVecNum> {
+        uv_matrix(&self.texture_transform)
+    }
+}
+
+impl RenderCategory {
+    /// The ids match the ones of the Kotlin `GltfRenderCategory` enum.
+    pub fn id(&self) -> i32 {
+        match self {
+            Self::Solid => 0,
+            Self::Cutout => 1,
+            Self::CutoutNoCull => 2,
+            Self::Translucent => 3,
+            Self::Emissive => 4,
+            Self::Additive => 5
+        }
+    }
+}
+
+impl MaterialFactors {
+    /// Returns the emissive factor scaled by the emissive strength.
+    pub fn emission(&self) -> EmissiveFactorInfo {
+        self.emissive_factor * self.emissive_strength
+    }
+}
+
+impl ImageUsage {
+    pub fn new(
+        color: bool,
+        alpha_cutoff: Option<GltfVecNum>
+    ) -> Self {
+        Self {
+            color,
+            alpha_cutoff
+        }
+    }
+
+    pub fn color(&self) -> bool {
+        self.color
+    }
+
+    pub fn alpha_cutoff(&self) -> Option<GltfVecNum> {
+        self.alpha_cutoff
+    }
+}
+
+impl RenderClass {
+    pub fn new(
+        category: RenderCategory,
+        shading_hint: ShadingHint,
+        texture_index: Option<usize>
+    ) -> Self {
+        Self {
+            category,
+            shading_hint,
+            texture_index,
+            emissive_layer: false,
+            emissive_texture_index: None
+        }
+    }
+
+    pub fn category(&self) -> RenderCategory {
+        self.category
+    }
+
+    pub fn shading_hint(&self) -> ShadingHint {
+        self.shading_hint
+    }
+
+    pub fn texture_index(&self) -> Option<usize> {
+        self.texture_index
+    }
+
+    pub fn emissive_layer(&self) -> bool {
+        self.emissive_layer
+    }
+
+    pub fn emissive_texture_index(&self) -> Option<usize> {
+        self.emissive_texture_index
+    }
+
+    pub fn set_emissive_layer(&mut self, emissive_layer: bool, emissive_texture_index: Option<usize>) {
+        self.emissive_layer = emissive_layer;
+        self.emissive_texture_index = emissive_texture_index
+    }
+}
+
+impl ShadingHint {
+    /// The ids match the ones of the Kotlin `GltfShadingHint` enum.
+    pub fn id(&self) -> i32 {
+        match self {
+            Self::Lit => 0,
+            Self::VanillaEntityLighting => 1,
+            Self::FullBright => 2
+        }
+    }
 }
@@ -0,0 +1,307 @@
+extern crate gltf;
+extern crate lazy_static;
+extern crate nalgebra;
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use gltf::json::Value;
+use nalgebra::Vector3;
+use crate::util::gltf::GltfVecNum;
+use super::material::{TextureReference, UNLIT_EXTENSION};
+
+/// The material extensions the loader handles by itself, outside the registry.
+pub const NATIVELY_HANDLED: [&str; 1] = [UNLIT_EXTENSION];
+
+/// The typed data of a material extension, parsed from its JSON.
+pub trait MaterialExtensionData: Any + Send + Sync {
+    /// The name of the extension, as it appears in the material's `extensions`.
+    const NAME: &'static str;
+
+    fn new_from_json(json: &Value) -> Self where Self: Sized;
+}
+
+/// Parses the JSON of one material extension into the data stored on the material.
+pub trait MaterialExtensionHandler: Send + Sync {
+    fn extension_name(&self) -> &str;
+
+    fn parse(&self, json: &Value) -> Box<dyn Any + Send + Sync>;
+}
+
+/// The handler of any extension whose data implements `MaterialExtensionData`.
+pub struct TypedMaterialExtensionHandler<T: MaterialExtensionData> {
+    phantom: PhantomData<fn() -> T>
+}
+
+pub struct MaterialExtensionRegistry {
+    handlers: HashMap<String, Box<dyn MaterialExtensionHandler>>
+}
+
+lazy_static::lazy_static! {
+    pub static ref MATERIAL_EXTENSION_REGISTRY: Mutex<MaterialExtensionRegistry> =
+        Mutex::new(MaterialExtensionRegistry::new_with_builtin());
+}
+
+impl<T: MaterialExtensionData> TypedMaterialExtensionHandler<T> {
+    pub fn new() -> Self {
+        Self { phantom: PhantomData }
+    }
+}
+
+impl<T: MaterialExtensionData> Default for TypedMaterialExtensionHandler<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: MaterialExtensionData> MaterialExtensionHandler for TypedMaterialExtensionHandler<T> {
+    fn extension_name(&self) -> &str {
+        T::NAME
+    }
+
+    fn parse(&self, json: &Value) -> Box<dyn Any + Send + Sync> {
+        Box::new(T::new_from_json(json))
+    }
+}
+
+impl Default for MaterialExtensionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MaterialExtensionRegistry {
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new()
+        }
+    }
+
+    /// Creates a registry holding the handlers of the ratified KHR material extensions.
+    pub fn new_with_builtin() -> Self {
+        let mut result = Self::new();
+        result.register_typed::<EmissiveStrength>();
+        result.register_typed::<Clearcoat>();
+        result.register_typed::<Sheen>();
+        result.register_typed::<Transmission>();
+        result.register_typed::<Ior>();
+        result.register_typed::<Specular>();
+        result.register_typed::<Volume>();
+        result.register_typed::<Iridescence>();
+        result.register_typed::<Anisotropy>();
+        result
+    }
+
+    /// Registers a handler, replacing the one previously registered for the same extension.
+    pub fn register(&mut self, handler: Box<dyn MaterialExtensionHandler>) {
+        self.handlers.insert(String::from(handler.extension_name()), handler);
+    }
+
+    pub fn register_typed<T: MaterialExtensionData>(&mut self) {
+        self.register(Box::new(TypedMaterialExtensionHandler::<T>::new()));
+    }
+
+    pub fn handler(&self, name: &str) -> Option<&dyn MaterialExtensionHandler> {
+        self.handlers.get(name).map(|it| it.as_ref())
+    }
+
+    pub fn is_handled(&self, name: &str) -> bool {
+        self.handlers.contains_key(name) || NATIVELY_HANDLED.contains(&name)
+    }
+}
+
+fn f32_of(json: &Value, key: &str, default: GltfVecNum) -> GltfVecNum {
+    json.get(key).and_then(Value::as_f64).map(|it| it as GltfVecNum).unwrap_or(default)
+}
+
+fn vec3_of(json: &Value, key: &str, default: Vector3<GltfVecNum>) -> Vector3<GltfVecNum> {
+    match json.get(key).and_then(Value::as_array) {
+        Some(arr) if arr.len() == 3 => Vector3::from_fn(|i, _| {
+            arr[i].as_f64().map(|it| it as GltfVecNum).unwrap_or(default[i])
+        }),
+        _ => default
+    }
+}
+
+fn texture_of(json: &Value, key: &str) -> Option<TextureReference> {
+    TextureReference::new_from_json(json.get(key)?)
+}
+
+/// `KHR_materials_emissive_strength`
+pub struct EmissiveStrength {
+    pub emissive_strength: GltfVecNum
+}
+
+/// `KHR_materials_clearcoat`
+pub struct Clearcoat {
+    pub clearcoat_factor: GltfVecNum,
+    pub clearcoat_texture: Option<TextureReference>,
+    pub clearcoat_roughness_factor: GltfVecNum,
+    pub clearcoat_roughness_texture: Option<TextureReference>,
+    pub clearcoat_normal_texture: Option<TextureReference>
+}
+
+/// `KHR_materials_sheen`
+pub struct Sheen {
+    pub sheen_color_factor: Vector3<GltfVecNum>,
+    pub sheen_color_texture: Option<TextureReference>,
+    pub sheen_roughness_factor: GltfVecNum,
+    pub sheen_roughness_texture: Option<TextureReference>
+}
+
+/// `KHR_materials_transmission`
+pub struct Transmission {
+    pub transmission_factor: GltfVecNum,
+    pub transmission_texture: Option<TextureReference>
+}
+
+/// `KHR_materials_ior`
+pub struct Ior {
+    pub ior: GltfVecNum
+}
+
+/// `KHR_materials_specular`
+pub struct Specular {
+    pub specular_factor: GltfVecNum,
+    pub specular_texture: Option<TextureReference>,
+    pub specular_color_factor: Vector3<GltfVecNum>,
+    pub specular_color_texture: Option<TextureReference>
+}
+
+/// `KHR_materials_volume`
+pub struct Volume {
+    pub thickness_factor: GltfVecNum,
+    pub thickness_texture: Option<TextureReference>,
+    /// Infinity if not defined in glTF.
+    pub attenuation_distance: GltfVecNum,
+    pub attenuation_color: Vector3<GltfVecNum>
+}
+
+/// `KHR_materials_iridescence`
+pub struct Iridescence {
+    pub iridescence_factor: GltfVecNum,
+    pub iridescence_texture: Option<TextureReference>,
+    pub iridescence_ior: GltfVecNum,
+    pub iridescence_thickness_minimum: GltfVecNum,
+    pub iridescence_thickness_maximum: GltfVecNum,
+    pub iridescence_thickness_texture: Option<TextureReference>
+}
+
+/// `KHR_materials_anisotropy`
+pub struct Anisotropy {
+    pub anisotropy_strength: GltfVecNum,
+    pub anisotropy_rotation: GltfVecNum,
+    pub anisotropy_texture: Option<TextureReference>
+}
+
+impl MaterialExtensionData for EmissiveStrength {
+    const NAME: &'static str = "KHR_materials_emissive_strength";
+
+    fn new_from_json(json: &Value) -> Self {
+        Self {
+            emissive_strength: f32_of(json, "emissiveStrength", 1.0)
+        }
+    }
+}
+
+impl MaterialExtensionData for Clearcoat {
+    const NAME: &'static str = "KHR_materials_clearcoat";
+
+    fn new_from_json(json: &Value) -> Self {
+        Self {
+            clearcoat_factor: f32_of(json, "clearcoatFactor", 0.0),
+            clearcoat_texture: texture_of(json, "clearcoatTexture"),
+            clearcoat_roughness_factor: f32_of(json, "clearcoatRoughnessFactor", 0.0),
+            clearcoat_roughness_texture: texture_of(json, "clearcoatRoughnessTexture"),
+            clearcoat_normal_texture: texture_of(json, "clearcoatNormalTexture")
+        }
+    }
+}
+
+impl MaterialExtensionData for Sheen {
+    const NAME: &'static str = "KHR_materials_sheen";
+
+    fn new_from_json(json: &Value) -> Self {
+        Self {
+            sheen_color_factor: vec3_of(json, "sheenColorFactor", Vector3::zeros()),
+            sheen_color_texture: texture_of(json, "sheenColorTexture"),
+            sheen_roughness_factor: f32_of(json, "sheenRoughnessFactor", 0.0),
+            sheen_roughness_texture: texture_of(json, "sheenRoughnessTexture")
+        }
+    }
+}
+
+impl MaterialExtensionData for Transmission {
+    const NAME: &'static str = "KHR_materials_transmission";
+
+    fn new_from_json(json: &Value) -> Self {
+        Self {
+            transmission_factor: f32_of(json, "transmissionFactor", 0.0),
+            transmission_texture: texture_of(json, "transmissionTexture")
+        }
+    }
+}
+
+impl MaterialExtensionData for Ior {
+    const NAME: &'static str = "KHR_materials_ior";
+
+    fn new_from_json(json: &Value) -> Self {
+        Self {
+            ior: f32_of(json, "ior", 1.5)
+        }
+    }
+}
+
+impl MaterialExtensionData for Specular {
+    const NAME: &'static str = "KHR_materials_specular";
+
+    fn new_from_json(json: &Value) -> Self {
+        Self {
+            specular_factor: f32_of(json, "specularFactor", 1.0),
+            specular_texture: texture_of(json, "specularTexture"),
+            specular_color_factor: vec3_of(json, "specularColorFactor", Vector3::new(1.0, 1.0, 1.0)),
+            specular_color_texture: texture_of(json, "specularColorTexture")
+        }
+    }
+}
+
+impl MaterialExtensionData for Volume {
+    const NAME: &'static str = "KHR_materials_volume";
+
+    fn new_from_json(json: &Value) -> Self {
+        Self {
+            thickness_factor: f32_of(json, "thicknessFactor", 0.0),
+            thickness_texture: texture_of(json, "thicknessTexture"),
+            attenuation_distance: f32_of(json, "attenuationDistance", GltfVecNum::INFINITY),
+            attenuation_color: vec3_of(json, "attenuationColor", Vector3::new(1.0, 1.0, 1.0))
+        }
+    }
+}
+
+impl MaterialExtensionData for Iridescence {
+    const NAME: &'static str = "KHR_materials_iridescence";
+
+    fn new_from_json(json: &Value) -> Self {
+        Self {
+            iridescence_factor: f32_of(json, "iridescenceFactor", 0.0),
+            iridescence_texture: texture_of(json, "iridescenceTexture"),
+            iridescence_ior: f32_of(json, "iridescenceIor", 1.3),
+            iridescence_thickness_minimum: f32_of(json, "iridescenceThicknessMinimum", 100.0),
+            iridescence_thickness_maximum: f32_of(json, "iridescenceThicknessMaximum", 400.0),
+            iridescence_thickness_texture: texture_of(json, "iridescenceThicknessTexture")
+        }
+    }
+}
+
+impl MaterialExtensionData for Anisotropy {
+    const NAME: &'static str = "KHR_materials_anisotropy";
+
+    fn new_from_json(json: &Value) -> Self {
+        Self {
+            anisotropy_strength: f32_of(json, "anisotropyStrength", 0.0),
+            anisotropy_rotation: f32_of(json, "anisotropyRotation", 0.0),
+            anisotropy_texture: texture_of(json, "anisotropyTexture")
+        }
+    }
+}
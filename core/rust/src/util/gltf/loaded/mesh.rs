@@ -1,5 +1,11 @@
 extern crate gltf;
 
+use gltf::json::Value;
+use crate::util::gltf::extras_to_string;
+
+/// The name of the material variants extension, both at the root and on primitives.
+pub const MATERIALS_VARIANTS_EXTENSION: &str = "KHR_materials_variants";
+
 pub struct PrimitiveInfo {
     index: usize,
     mode: gltf::mesh::Mode,
@@ -8,7 +14,17 @@ pub struct PrimitiveInfo {
     attributes: Vec<AttributeInfo>,
     morph_targets: Vec<MorphTargetInfo>,
     /// None if not defined in glTF.
-    material: Option<usize>
+    material: Option<usize>,
+    /// Empty if `KHR_materials_variants` is not present on the primitive.
+    variant_mappings: Vec<VariantMappingInfo>,
+    /// The `extras` of the primitive as JSON text. None if not defined in glTF.
+    extras: Option<String>
+}
+
+/// One mapping of `KHR_materials_variants`: the material used by the listed variants.
+pub struct VariantMappingInfo {
+    material: usize,
+    variants: Vec<usize>
 }
 
 pub struct AttributeInfo {
@@ -32,7 +48,8 @@ impl PrimitiveInfo {
         indices: Option<usize>,
         attributes: Vec<AttributeInfo>,
         morph_targets: Vec<MorphTargetInfo>,
-        material: Option<usize>
+        material: Option<usize>,
+        variant_mappings: Vec<VariantMappingInfo>
     ) -> Self {
         Self {
             index,
@@ -40,7 +57,9 @@ impl PrimitiveInfo {
             indices,
             attributes,
             morph_targets,
-            material
+            material,
+            variant_mappings,
+            extras: None
         }
     }
 
@@ -49,13 +68,7 @@ impl PrimitiveInfo {
     ) -> Self {
         let index = primitive.index();
         let mode = primitive.mode();
-        let indices;
-        match primitive.indices() {
-            Some(accessor) => {
-                indices = Some(accessor.index());
-            }
-            None => indices = None
-        }
+        let indices = primitive.indices().map(|accessor| accessor.index());
         let mut attributes = Vec::new();
         for attr in primitive.attributes() {
             let sem = attr.0;
@@ -77,8 +90,15 @@ impl PrimitiveInfo {
             morph_targets.push(target_info)
         }
         let material = primitive.material().index();
+        let variant_mappings = primitive.extension_value(MATERIALS_VARIANTS_EXTENSION)
+            .and_then(|it| it.get("mappings"))
+            .and_then(Value::as_array)
+            .map(|it| it.iter().filter_map(VariantMappingInfo::new_from_json).collect())
+            .unwrap_or_default();
 
-        Self::new(index, mode, indices, attributes, morph_targets, material)
+        let mut result = Self::new(index, mode, indices, attributes, morph_targets, material, variant_mappings);
+        result.extras = extras_to_string(primitive.extras());
+        result
     }
 
     pub fn index(&self) -> usize {
@@ -89,6 +109,10 @@ impl PrimitiveInfo {
         self.mode
     }
 
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
     pub fn indices(&self) -> Option<usize> {
         self.indices
     }
@@ -104,6 +128,56 @@ impl PrimitiveInfo {
     pub fn material(&self) -> Option<usize> {
         self.material
     }
+
+    pub fn variant_mappings(&self) -> &Vec<VariantMappingInfo> {
+        &self.variant_mappings
+    }
+
+    /// Returns the material the primitive uses for the variant, falling back to
+    /// its default material if no mapping lists the variant.
+    pub fn material_for_variant(&self, variant: usize) -> Option<usize> {
+        self.variant_mappings.iter()
+            .find(|it| it.variants.contains(&variant))
+            .map(|it| it.material)
+            .or(self.material)
+    }
+
+    /// Returns the accessor index of the attribute, None if the primitive lacks it.
+    pub fn attribute_accessor(&self, semantic: &gltf::mesh::Semantic) -> Option<usize> {
+        self.attributes.iter()
+            .find(|it| it.type_semantic == *semantic)
+            .map(|it| it.accessor_index)
+    }
+}
+
+impl VariantMappingInfo {
+    pub fn new(
+        material: usize,
+        variants: Vec<usize>
+    ) -> Self {
+        Self {
+            material,
+            variants
+        }
+    }
+
+    /// Returns None if the mapping has no material.
+    pub fn new_from_json(json: &Value) -> Option<Self> {
+        let material = json.get("material")?.as_u64()? as usize;
+        let variants = json.get("variants")
+            .and_then(Value::as_array)
+            .map(|it| it.iter().filter_map(Value::as_u64).map(|it| it as usize).collect())
+            .unwrap_or_default();
+        Some(Self::new(material, variants))
+    }
+
+    pub fn material(&self) -> usize {
+        self.material
+    }
+
+    pub fn variants(&self) -> &Vec<usize> {
+        &self.variants
+    }
 }
 
 impl AttributeInfo {
@@ -1,22 +1,38 @@
+pub mod animation;
+pub mod asset;
+pub mod buffer_view;
+pub mod draco;
+pub mod light;
 pub mod material;
+pub mod material_extension;
 pub mod mesh;
+pub mod node;
+pub mod scene;
 
-use std::ops::Index;
-use gltf::{Accessor, buffer, Material, Mesh, texture};
+use std::any::Any;
+use std::collections::HashMap;
+use gltf::{Accessor, buffer, Material, Mesh, Node, texture};
+use crate::constants;
 use crate::util;
+use crate::util::image::NativeImageFormat;
+use image::ImageFormat;
 use std::sync::{Arc, Mutex};
-use gltf::accessor::Dimensions;
+use gltf::accessor::{DataType, Dimensions};
 use gltf::buffer::View;
 use gltf::json::Value;
+use gltf::material::AlphaMode;
+use gltf::mesh::Semantic;
 use gltf::texture::{MagFilter, MinFilter, WrappingMode};
 use nalgebra::{Matrix2, Matrix3, Matrix4, SMatrix, SVector, Vector2, Vector3, Vector4};
-use super::{GltfVecNum, mag_filter_to_gl_value, min_filter_to_gl_value, wrapping_mode_to_gl_value};
+use super::{AnimationState, ConstraintSet, GltfVecNum, NodePose, extras_to_string, mag_filter_to_gl_value, min_filter_to_gl_value, wrapping_mode_to_gl_value};
 
 pub struct LoadedGltfBuffer<'a> {
     gltf: Arc<Mutex<LoadedGltf<'a>>>,
     index: usize,
     uri: String,
-    data: Vec<u8>
+    data: Vec<u8>,
+    /// The `extras` of the object as JSON text. None if not defined in glTF.
+    extras: Option<String>
 }
 
 pub struct LoadedGltfBufferView<'a> {
@@ -26,25 +42,43 @@ pub struct LoadedGltfBufferView<'a> {
     data_offset: usize,
     data_length: usize,
     data_stride: Option<usize>,
-    target: Option<buffer::Target>
+    target: Option<buffer::Target>,
+    /// The decompressed bytes of the view if it is compressed by `EXT_meshopt_compression`,
+    /// which take the place of the bytes of the (fallback) buffer.
+    decoded_data: Option<Vec<u8>>,
+    /// The `extras` of the object as JSON text. None if not defined in glTF.
+    extras: Option<String>
 }
 
 pub struct  LoadedGltfAccessor<'a> {
     gltf: Arc<Mutex<LoadedGltf<'a>>>,
     index: usize,
-    buffer_view_index: usize,
+    /// None if the accessor has no buffer view, its elements then being zeros
+    /// unless decoded data is given.
+    buffer_view_index: Option<usize>,
     comp_size: usize,
     comp_count: usize,
     max_values: Option<Vec<Value>>,
     min_values: Option<Vec<Value>>,
-    dimensions: Dimensions
+    dimensions: Dimensions,
+    data_type: DataType,
+    normalized: bool,
+    /// The offset of the first element within the buffer view, in bytes.
+    data_offset: usize,
+    /// The tightly packed elements of the accessor if they are decoded from
+    /// a compressed primitive, which take the place of the buffer view.
+    decoded_data: Option<Vec<u8>>,
+    /// The `extras` of the object as JSON text. None if not defined in glTF.
+    extras: Option<String>
 }
 
 pub struct LoadedGltfImage<'a> {
     gltf: Arc<Mutex<LoadedGltf<'a>>>,
     index: usize,
     uri: String,
-    data: Vec<u8>
+    data: Vec<u8>,
+    /// The `extras` of the object as JSON text. None if not defined in glTF.
+    extras: Option<String>
 }
 
 pub struct LoadedGltfSampler<'a> {
@@ -55,14 +89,25 @@ pub struct LoadedGltfSampler<'a> {
     min_filter: Option<MinFilter>,
     name: Option<String>,
     wrap_s: WrappingMode,
-    wrap_t: WrappingMode
+    wrap_t: WrappingMode,
+    /// The `extras` of the object as JSON text. None if not defined in glTF.
+    extras: Option<String>
 }
 
 pub struct LoadedGltfTexture<'a> {
     gltf: Arc<Mutex<LoadedGltf<'a>>>,
     index: usize,
-    source_index: usize,
-    sampler_index: usize
+    /// The image the texture is sampled from: the source given by an image format
+    /// extension if its format is decodable, or the core `source` otherwise.
+    /// None if there is no usable source.
+    source_index: Option<usize>,
+    /// The core `source` of the texture if an extension source has been chosen
+    /// over it, to be used where the extension source cannot.
+    fallback_source_index: Option<usize>,
+    /// None if the texture uses the default sampler.
+    sampler_index: Option<usize>,
+    /// The `extras` of the object as JSON text. None if not defined in glTF.
+    extras: Option<String>
 }
 
 pub struct LoadedGltfMaterial<'a> {
@@ -73,7 +118,21 @@ pub struct LoadedGltfMaterial<'a> {
     normal_texture: Option<material::NormalTextureInfo>,
     occlusion_texture: Option<material::OcclusionTextureInfo>,
     emissive_texture: Option<material::EmissiveTextureInfo>,
-    emissive_factor: material::EmissiveFactorInfo
+    emissive_factor: material::EmissiveFactorInfo,
+    alpha_mode: AlphaMode,
+    /// Only meaningful with the MASK alpha mode, 0.5 if not defined in glTF.
+    alpha_cutoff: GltfVecNum,
+    double_sided: bool,
+    /// None if not defined in glTF.
+    name: Option<String>,
+    /// Whether `KHR_materials_unlit` is present on the material.
+    unlit: bool,
+    /// The data of the extensions parsed by the registered handlers, by extension name.
+    extensions: HashMap<String, Box<dyn Any + Send + Sync>>,
+    /// The names of the extensions present on the material but handled by nothing.
+    unhandled_extensions: Vec<String>,
+    /// The `extras` of the object as JSON text. None if not defined in glTF.
+    extras: Option<String>
 }
 
 pub struct LoadedGltfMesh<'a> {
@@ -81,18 +140,58 @@ pub struct LoadedGltfMesh<'a> {
     index: usize,
     primitives: Vec<mesh::PrimitiveInfo>,
     /// Empty if not defined in glTF, or defined but values are not given.
-    weights: Vec<f32>
+    weights: Vec<f32>,
+    /// The `extras` of the object as JSON text. None if not defined in glTF.
+    extras: Option<String>
+}
+
+pub struct LoadedGltfNode<'a> {
+    gltf: Arc<Mutex<LoadedGltf<'a>>>,
+    index: usize,
+    /// None if not defined in glTF.
+    name: Option<String>,
+    /// None if the node is a root node of the scene hierarchy.
+    parent_index: Option<usize>,
+    children: Vec<usize>,
+    /// None if not defined in glTF.
+    mesh_index: Option<usize>,
+    /// The `KHR_lights_punctual` light of the node. None if not defined in glTF.
+    light_index: Option<usize>,
+    transform: node::TransformInfo,
+    /// The `EXT_mesh_gpu_instancing` instance transforms, relative to the node.
+    /// Empty if the node is not instanced.
+    instances: Vec<node::TransformInfo>,
+    /// The `extras` of the object as JSON text. None if not defined in glTF.
+    extras: Option<String>
 }
 
 pub struct LoadedGltf<'a> {
+    asset: asset::AssetInfo,
     buffers: Vec<LoadedGltfBuffer<'a>>,
     buffer_views: Vec<LoadedGltfBufferView<'a>>,
     accessors: Vec<LoadedGltfAccessor<'a>>,
     images: Vec<LoadedGltfImage<'a>>,
     samplers: Vec<LoadedGltfSampler<'a>>,
+    /// The sampler of the textures which have none. Created along with the wrapper.
+    default_sampler: Option<LoadedGltfSampler<'a>>,
     textures: Vec<LoadedGltfTexture<'a>>,
     materials: Vec<LoadedGltfMaterial<'a>>,
-    meshes: Vec<LoadedGltfMesh<'a>>
+    /// The material of the primitives which have none. Created along with the wrapper.
+    default_material: Option<LoadedGltfMaterial<'a>>,
+    meshes: Vec<LoadedGltfMesh<'a>>,
+    nodes: Vec<LoadedGltfNode<'a>>,
+    /// The `KHR_lights_punctual` lights, by light index.
+    lights: Vec<light::LightInfo>,
+    /// The names of the `KHR_materials_variants` variants, by variant index.
+    variants: Vec<String>,
+    animations: Vec<animation::AnimationInfo>,
+    scenes: Vec<scene::SceneInfo>,
+    /// The `scene` of the glTF, None if it names no default scene.
+    default_scene_index: Option<usize>,
+    constraints: ConstraintSet,
+    /// Whether unlit materials are rendered full-bright rather than with
+    /// the vanilla entity lighting.
+    unlit_full_bright: bool
 }
 
 pub struct LoadedGltfWrapper<'a> {
@@ -110,7 +209,8 @@ impl<'a> LoadedGltfBuffer<'a> {
             gltf: Arc::clone(gltf),
             index,
             uri,
-            data
+            data,
+            extras: None
         }
     }
 
@@ -118,6 +218,14 @@ impl<'a> LoadedGltfBuffer<'a> {
         Arc::clone(&self.gltf)
     }
 
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    pub fn set_extras(&mut self, extras: Option<String>) {
+        self.extras = extras
+    }
+
     pub fn index(&self) -> usize {
         self.index
     }
@@ -162,19 +270,31 @@ impl<'a> LoadedGltfBufferView<'a> {
             data_offset,
             data_length,
             data_stride,
-            target
+            target,
+            decoded_data: None,
+            extras: None
         }
     }
 
     pub fn new_from_view(gltf: &Arc<Mutex<LoadedGltf<'a>>>, view: &View) -> Self {
-        Self::new(gltf, view.index(), view.buffer().index(), view.offset(),
-                  view.length(), view.stride(), view.target())
+        let mut result = Self::new(gltf, view.index(), view.buffer().index(), view.offset(),
+                                   view.length(), view.stride(), view.target());
+        result.extras = extras_to_string(view.extras());
+        result
     }
 
     pub fn gltf(&self) -> Arc<Mutex<LoadedGltf<'a>>> {
         Arc::clone(&self.gltf)
     }
 
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    pub fn set_extras(&mut self, extras: Option<String>) {
+        self.extras = extras
+    }
+
     pub fn buffer_index(&self) -> usize {
         self.buffer_index
     }
@@ -199,40 +319,65 @@ impl<'a> LoadedGltfBufferView<'a> {
         self.target
     }
 
+    pub fn decoded_data(&self) -> Option<&Vec<u8>> {
+        self.decoded_data.as_ref()
+    }
+
+    pub fn set_decoded_data(&mut self, decoded_data: Option<Vec<u8>>) {
+        self.decoded_data = decoded_data
+    }
+
     pub fn load_data(&self) -> Vec<u8> {
         self.load_data_strided(0)
     }
 
+    /// Returns the bytes of the view, borrowed from its buffer (or its decoded
+    /// data) within the already locked glTF.
+    ///
+    /// The view is cut short where its buffer ends (and is empty if its buffer
+    /// is missing), since nothing checks the loaded bytes against its range.
+    pub fn data_slice<'b>(&'b self, gltf: &'b LoadedGltf) -> &'b [u8] {
+        match &self.decoded_data {
+            Some(data) => data.as_slice(),
+            None => {
+                let data = match gltf.buffers.get(self.buffer_index) {
+                    Some(buffer) => buffer.data(),
+                    None => return &[]
+                };
+                let start = self.data_offset.min(data.len());
+                let end = self.data_offset.saturating_add(self.data_length).min(data.len());
+                &data[start..end]
+            }
+        }
+    }
+
+    /// Copies `data_length` bytes of the view starting at the given stride,
+    /// the bytes beyond the end of the view being zero.
     pub fn load_data_strided(&self, stride_count: u32) -> Vec<u8> {
         let gltf = self.gltf.lock().unwrap();
-        let buffer = gltf.buffers().get(self.buffer_index).unwrap();
+        let data = self.data_slice(&gltf);
+        let start = ((stride_count as usize) * self.data_stride.unwrap_or(0)).min(data.len());
+        let end = (start + self.data_length).min(data.len());
         let mut result = util::new_buffer_vec(self.data_length, 0u8);
-        let result_ptr = result.as_mut_ptr();
-        unsafe {
-            let data_ptr = buffer.data_ptr().add(self.data_offset)
-                .add((stride_count as usize) * self.data_stride.unwrap_or(0));
-            let mut result_ptrm = result_ptr;
-            let mut data_ptrm = data_ptr;
-            for _ in 0..self.data_length {
-                *result_ptrm = *data_ptrm;
-                result_ptrm = result_ptrm.add(1);
-                data_ptrm = data_ptrm.add(1);
-            }
-        }
+        result[..end - start].copy_from_slice(&data[start..end]);
         result
     }
 }
 
 impl<'a> LoadedGltfAccessor<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         gltf: &Arc<Mutex<LoadedGltf<'a>>>,
         index: usize,
-        buffer_view_index: usize,
+        buffer_view_index: Option<usize>,
         comp_size: usize,
         comp_count: usize,
         max_values: Option<Vec<Value>>,
         min_values: Option<Vec<Value>>,
-        dimensions: Dimensions
+        dimensions: Dimensions,
+        data_type: DataType,
+        normalized: bool,
+        data_offset: usize
     ) -> Self {
         Self {
             gltf: Arc::clone(gltf),
@@ -242,7 +387,12 @@ impl<'a> LoadedGltfAccessor<'a> {
             comp_count,
             max_values,
             min_values,
-            dimensions
+            dimensions,
+            data_type,
+            normalized,
+            data_offset,
+            decoded_data: None,
+            extras: None
         }
     }
 
@@ -250,64 +400,39 @@ impl<'a> LoadedGltfAccessor<'a> {
         gltf: &Arc<Mutex<LoadedGltf<'a>>>,
         accessor: &Accessor
     ) -> Option<Self> {
-        if let Some(max_val) = accessor.max() {
-            if let Value::Array(max_val_arr) = max_val {
-                if let Some(min_val) = accessor.min() {
-                    if let Value::Array(min_val_arr) = min_val {
-                        Some(Self::new(
-                            gltf, accessor.index(), accessor.view().unwrap().index(),accessor.size(),
-                            accessor.count(), Some(max_val_arr), Some(min_val_arr), accessor.dimensions()))
-                    } else {
-                        Some(Self::new(
-                            gltf, accessor.index(), accessor.view().unwrap().index(),
-                            accessor.size(), accessor.count(), Some(max_val_arr), None, accessor.dimensions()))
-                    }
-                } else {
-                    Some(Self::new(
-                        gltf, accessor.index(), accessor.view().unwrap().index(),
-                        accessor.size(), accessor.count(), Some(max_val_arr), None, accessor.dimensions()))
-                }
-            } else if let Some(min_val) = accessor.min() {
-                if let Value::Array(min_val_arr) = min_val {
-                    Some(Self::new(
-                        gltf, accessor.index(), accessor.view().unwrap().index(),
-                        accessor.size(), accessor.count(), None, Some(min_val_arr), accessor.dimensions()))
-                } else {
-                    Some(Self::new(
-                        gltf, accessor.index(), accessor.view().unwrap().index(),
-                        accessor.size(), accessor.count(), None, None, accessor.dimensions()))
-                }
-            } else {
-                Some(Self::new(
-                    gltf, accessor.index(), accessor.view().unwrap().index(),
-                    accessor.size(), accessor.count(), None, None, accessor.dimensions()))
-            }
-        } else if let Some(min_val) = accessor.min() {
-            if let Value::Array(min_val_arr) = min_val {
-                Some(Self::new(
-                    gltf, accessor.index(), accessor.view().unwrap().index(),
-                    accessor.size(), accessor.count(), None, Some(min_val_arr), accessor.dimensions()))
-            } else {
-                Some(Self::new(
-                    gltf, accessor.index(), accessor.view().unwrap().index(),
-                    accessor.size(), accessor.count(), None, None, accessor.dimensions()))
-            }
-        } else {
-            Some(Self::new(
-                gltf, accessor.index(), accessor.view().unwrap().index(),
-                accessor.size(), accessor.count(), None, None, accessor.dimensions()))
-        }
+        let max_values = match accessor.max() {
+            Some(Value::Array(max_val_arr)) => Some(max_val_arr),
+            _ => None
+        };
+        let min_values = match accessor.min() {
+            Some(Value::Array(min_val_arr)) => Some(min_val_arr),
+            _ => None
+        };
+        let mut result = Self::new(
+            gltf, accessor.index(), accessor.view().map(|it| it.index()),
+            accessor.size(), accessor.count(), max_values, min_values, accessor.dimensions(),
+            accessor.data_type(), accessor.normalized(), accessor.offset());
+        result.extras = extras_to_string(accessor.extras());
+        Some(result)
     }
 
     pub fn gltf(&self) -> Arc<Mutex<LoadedGltf<'a>>> {
         Arc::clone(&self.gltf)
     }
 
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    pub fn set_extras(&mut self, extras: Option<String>) {
+        self.extras = extras
+    }
+
     pub fn index(&self) -> usize {
         self.index
     }
 
-    pub fn buffer_view_index(&self) -> usize {
+    pub fn buffer_view_index(&self) -> Option<usize> {
         self.buffer_view_index
     }
 
@@ -331,291 +456,150 @@ impl<'a> LoadedGltfAccessor<'a> {
         self.dimensions
     }
 
-    pub fn load_data(&self) -> Vec<LoadedGltfAccessorDatum> {
-        let gltf = self.gltf.lock().unwrap();
-        let buffer_view = gltf.buffer_views.get(self.buffer_view_index).unwrap();
-        let mut result = Vec::new();
+    pub fn data_type(&self) -> DataType {
+        self.data_type
+    }
 
-        for i in 0..self.comp_count {
-            let datum_content = buffer_view.load_data_strided(i as u32);
-            let datum = match self.dimensions {
-                Dimensions::Scalar => {
-                    let a0 = *datum_content.index(0);
-                    let a1 = *datum_content.index(1);
-                    let a2 = *datum_content.index(2);
-                    let a3 = *datum_content.index(3);
+    pub fn normalized(&self) -> bool {
+        self.normalized
+    }
 
-                    let a = util::bits::u8_to_f32((a0, a1, a2, a3));
+    pub fn data_offset(&self) -> usize {
+        self.data_offset
+    }
 
-                    LoadedGltfAccessorDatum::scalar_of(a)
-                }
-                Dimensions::Vec2 => {
-                    let x0 = *datum_content.index(0);
-                    let x1 = *datum_content.index(1);
-                    let x2 = *datum_content.index(2);
-                    let x3 = *datum_content.index(3);
-                    let y0 = *datum_content.index(4);
-                    let y1 = *datum_content.index(5);
-                    let y2 = *datum_content.index(6);
-                    let y3 = *datum_content.index(7);
-
-                    let x = util::bits::u8_to_f32((x0, x1, x2, x3));
-                    let y = util::bits::u8_to_f32((y0, y1, y2, y3));
-
-                    LoadedGltfAccessorDatum::vec2_of(x, y)
-                }
-                Dimensions::Vec3 => {
-                    let x0 = *datum_content.index(0);
-                    let x1 = *datum_content.index(1);
-                    let x2 = *datum_content.index(2);
-                    let x3 = *datum_content.index(3);
-                    let y0 = *datum_content.index(4);
-                    let y1 = *datum_content.index(5);
-                    let y2 = *datum_content.index(6);
-                    let y3 = *datum_content.index(7);
-                    let z0 = *datum_content.index(8);
-                    let z1 = *datum_content.index(9);
-                    let z2 = *datum_content.index(10);
-                    let z3 = *datum_content.index(11);
-
-                    let x = util::bits::u8_to_f32((x0, x1, x2, x3));
-                    let y = util::bits::u8_to_f32((y0, y1, y2, y3));
-                    let z = util::bits::u8_to_f32((z0, z1, z2, z3));
-
-                    LoadedGltfAccessorDatum::vec3_of(x, y, z)
-                }
-                Dimensions::Vec4 => {
-                    let x0 = *datum_content.index(0);
-                    let x1 = *datum_content.index(1);
-                    let x2 = *datum_content.index(2);
-                    let x3 = *datum_content.index(3);
-                    let y0 = *datum_content.index(4);
-                    let y1 = *datum_content.index(5);
-                    let y2 = *datum_content.index(6);
-                    let y3 = *datum_content.index(7);
-                    let z0 = *datum_content.index(8);
-                    let z1 = *datum_content.index(9);
-                    let z2 = *datum_content.index(10);
-                    let z3 = *datum_content.index(11);
-                    let w0 = *datum_content.index(12);
-                    let w1 = *datum_content.index(13);
-                    let w2 = *datum_content.index(14);
-                    let w3 = *datum_content.index(15);
-
-                    let x = util::bits::u8_to_f32((x0, x1, x2, x3));
-                    let y = util::bits::u8_to_f32((y0, y1, y2, y3));
-                    let z = util::bits::u8_to_f32((z0, z1, z2, z3));
-                    let w = util::bits::u8_to_f32((w0, w1, w2, w3));
-
-                    LoadedGltfAccessorDatum::vec4_of(x, y, z, w)
-                }
-                Dimensions::Mat2 => {
-                    let a00_0 = *datum_content.index(0);
-                    let a00_1 = *datum_content.index(1);
-                    let a00_2 = *datum_content.index(2);
-                    let a00_3 = *datum_content.index(3);
-
-                    let a01_0 = *datum_content.index(4);
-                    let a01_1 = *datum_content.index(5);
-                    let a01_2 = *datum_content.index(6);
-                    let a01_3 = *datum_content.index(7);
-
-                    let a10_0 = *datum_content.index(8);
-                    let a10_1 = *datum_content.index(9);
-                    let a10_2 = *datum_content.index(10);
-                    let a10_3 = *datum_content.index(11);
-
-                    let a11_0 = *datum_content.index(12);
-                    let a11_1 = *datum_content.index(13);
-                    let a11_2 = *datum_content.index(14);
-                    let a11_3 = *datum_content.index(15);
-
-                    let a00 = util::bits::u8_to_f32((a00_0, a00_1, a00_2, a00_3));
-                    let a01 = util::bits::u8_to_f32((a01_0, a01_1, a01_2, a01_3));
-                    let a10 = util::bits::u8_to_f32((a10_0, a10_1, a10_2, a10_3));
-                    let a11 = util::bits::u8_to_f32((a11_0, a11_1, a11_2, a11_3));
-
-                    LoadedGltfAccessorDatum::mat2_of(
-                        a00, a01,
-                        a10, a11
-                    )
-                }
-                Dimensions::Mat3 => {
-                    let a00_0 = *datum_content.index(0);
-                    let a00_1 = *datum_content.index(1);
-                    let a00_2 = *datum_content.index(2);
-                    let a00_3 = *datum_content.index(3);
-
-                    let a01_0 = *datum_content.index(4);
-                    let a01_1 = *datum_content.index(5);
-                    let a01_2 = *datum_content.index(6);
-                    let a01_3 = *datum_content.index(7);
-
-                    let a02_0 = *datum_content.index(8);
-                    let a02_1 = *datum_content.index(9);
-                    let a02_2 = *datum_content.index(10);
-                    let a02_3 = *datum_content.index(11);
-
-                    let a10_0 = *datum_content.index(12);
-                    let a10_1 = *datum_content.index(13);
-                    let a10_2 = *datum_content.index(14);
-                    let a10_3 = *datum_content.index(15);
-
-                    let a11_0 = *datum_content.index(16);
-                    let a11_1 = *datum_content.index(17);
-                    let a11_2 = *datum_content.index(18);
-                    let a11_3 = *datum_content.index(19);
-
-                    let a12_0 = *datum_content.index(20);
-                    let a12_1 = *datum_content.index(21);
-                    let a12_2 = *datum_content.index(22);
-                    let a12_3 = *datum_content.index(23);
-
-                    let a20_0 = *datum_content.index(24);
-                    let a20_1 = *datum_content.index(25);
-                    let a20_2 = *datum_content.index(26);
-                    let a20_3 = *datum_content.index(27);
-
-                    let a21_0 = *datum_content.index(28);
-                    let a21_1 = *datum_content.index(29);
-                    let a21_2 = *datum_content.index(30);
-                    let a21_3 = *datum_content.index(31);
-
-                    let a22_0 = *datum_content.index(32);
-                    let a22_1 = *datum_content.index(33);
-                    let a22_2 = *datum_content.index(34);
-                    let a22_3 = *datum_content.index(35);
-
-                    let a00 = util::bits::u8_to_f32((a00_0, a00_1, a00_2, a00_3));
-                    let a01 = util::bits::u8_to_f32((a01_0, a01_1, a01_2, a01_3));
-                    let a02 = util::bits::u8_to_f32((a02_0, a02_1, a02_2, a02_3));
-                    let a10 = util::bits::u8_to_f32((a10_0, a10_1, a10_2, a10_3));
-                    let a11 = util::bits::u8_to_f32((a11_0, a11_1, a11_2, a11_3));
-                    let a12 = util::bits::u8_to_f32((a12_0, a12_1, a12_2, a12_3));
-                    let a20 = util::bits::u8_to_f32((a20_0, a20_1, a20_2, a20_3));
-                    let a21 = util::bits::u8_to_f32((a21_0, a21_1, a21_2, a21_3));
-                    let a22 = util::bits::u8_to_f32((a22_0, a22_1, a22_2, a22_3));
-
-                    LoadedGltfAccessorDatum::mat3_of(
-                        a00, a01, a02,
-                        a10, a11, a12,
-                        a20, a21, a22
-                    )
-                }
-                Dimensions::Mat4 => {
-                    let a00_0 = *datum_content.index(0);
-                    let a00_1 = *datum_content.index(1);
-                    let a00_2 = *datum_content.index(2);
-                    let a00_3 = *datum_content.index(3);
-
-                    let a01_0 = *datum_content.index(4);
-                    let a01_1 = *datum_content.index(5);
-                    let a01_2 = *datum_content.index(6);
-                    let a01_3 = *datum_content.index(7);
-
-                    let a02_0 = *datum_content.index(8);
-                    let a02_1 = *datum_content.index(9);
-                    let a02_2 = *datum_content.index(10);
-                    let a02_3 = *datum_content.index(11);
-
-                    let a03_0 = *datum_content.index(12);
-                    let a03_1 = *datum_content.index(13);
-                    let a03_2 = *datum_content.index(14);
-                    let a03_3 = *datum_content.index(15);
-
-                    let a10_0 = *datum_content.index(16);
-                    let a10_1 = *datum_content.index(17);
-                    let a10_2 = *datum_content.index(18);
-                    let a10_3 = *datum_content.index(19);
-
-                    let a11_0 = *datum_content.index(20);
-                    let a11_1 = *datum_content.index(21);
-                    let a11_2 = *datum_content.index(22);
-                    let a11_3 = *datum_content.index(23);
-
-                    let a12_0 = *datum_content.index(24);
-                    let a12_1 = *datum_content.index(25);
-                    let a12_2 = *datum_content.index(26);
-                    let a12_3 = *datum_content.index(27);
-
-                    let a13_0 = *datum_content.index(28);
-                    let a13_1 = *datum_content.index(29);
-                    let a13_2 = *datum_content.index(30);
-                    let a13_3 = *datum_content.index(31);
-
-                    let a20_0 = *datum_content.index(32);
-                    let a20_1 = *datum_content.index(33);
-                    let a20_2 = *datum_content.index(34);
-                    let a20_3 = *datum_content.index(35);
-
-                    let a21_0 = *datum_content.index(36);
-                    let a21_1 = *datum_content.index(37);
-                    let a21_2 = *datum_content.index(38);
-                    let a21_3 = *datum_content.index(39);
-
-                    let a22_0 = *datum_content.index(40);
-                    let a22_1 = *datum_content.index(41);
-                    let a22_2 = *datum_content.index(42);
-                    let a22_3 = *datum_content.index(43);
-
-                    let a23_0 = *datum_content.index(44);
-                    let a23_1 = *datum_content.index(45);
-                    let a23_2 = *datum_content.index(46);
-                    let a23_3 = *datum_content.index(47);
-
-                    let a30_0 = *datum_content.index(48);
-                    let a30_1 = *datum_content.index(49);
-                    let a30_2 = *datum_content.index(50);
-                    let a30_3 = *datum_content.index(51);
-
-                    let a31_0 = *datum_content.index(52);
-                    let a31_1 = *datum_content.index(53);
-                    let a31_2 = *datum_content.index(54);
-                    let a31_3 = *datum_content.index(55);
-
-                    let a32_0 = *datum_content.index(56);
-                    let a32_1 = *datum_content.index(57);
-                    let a32_2 = *datum_content.index(58);
-                    let a32_3 = *datum_content.index(59);
-
-                    let a33_0 = *datum_content.index(60);
-                    let a33_1 = *datum_content.index(61);
-                    let a33_2 = *datum_content.index(62);
-                    let a33_3 = *datum_content.index(63);
-
-                    let a00 = util::bits::u8_to_f32((a00_0, a00_1, a00_2, a00_3));
-                    let a01 = util::bits::u8_to_f32((a01_0, a01_1, a01_2, a01_3));
-                    let a02 = util::bits::u8_to_f32((a02_0, a02_1, a02_2, a02_3));
-                    let a03 = util::bits::u8_to_f32((a03_0, a03_1, a03_2, a03_3));
-                    let a10 = util::bits::u8_to_f32((a10_0, a10_1, a10_2, a10_3));
-                    let a11 = util::bits::u8_to_f32((a11_0, a11_1, a11_2, a11_3));
-                    let a12 = util::bits::u8_to_f32((a12_0, a12_1, a12_2, a12_3));
-                    let a13 = util::bits::u8_to_f32((a13_0, a13_1, a13_2, a13_3));
-                    let a20 = util::bits::u8_to_f32((a20_0, a20_1, a20_2, a20_3));
-                    let a21 = util::bits::u8_to_f32((a21_0, a21_1, a21_2, a21_3));
-                    let a22 = util::bits::u8_to_f32((a22_0, a22_1, a22_2, a22_3));
-                    let a23 = util::bits::u8_to_f32((a23_0, a23_1, a23_2, a23_3));
-                    let a30 = util::bits::u8_to_f32((a30_0, a30_1, a30_2, a30_3));
-                    let a31 = util::bits::u8_to_f32((a31_0, a31_1, a31_2, a31_3));
-                    let a32 = util::bits::u8_to_f32((a32_0, a32_1, a32_2, a32_3));
-                    let a33 = util::bits::u8_to_f32((a33_0, a33_1, a33_2, a33_3));
-
-                    LoadedGltfAccessorDatum::mat4_of(
-                        a00, a01, a02, a03,
-                        a10, a11, a12, a13,
-                        a20, a21, a22, a23,
-                        a30, a31, a32, a33
-                    )
-                }
-            };
-            result.push(datum);
+    pub fn decoded_data(&self) -> Option<&Vec<u8>> {
+        self.decoded_data.as_ref()
+    }
+
+    pub fn set_decoded_data(&mut self, decoded_data: Option<Vec<u8>>) {
+        self.decoded_data = decoded_data
+    }
+
+    /// Returns the bytes holding the elements, the offset of the first element
+    /// and the stride between elements, or None if the accessor has no data.
+    fn element_layout<'b>(&'b self, gltf: &'b LoadedGltf) -> Option<(&'b [u8], usize, usize)> {
+        if let Some(data) = &self.decoded_data {
+            return Some((data.as_slice(), 0, self.comp_size));
         }
+        let buffer_view = gltf.buffer_views.get(self.buffer_view_index?)?;
+        Some((buffer_view.data_slice(gltf), self.data_offset,
+              buffer_view.data_stride().unwrap_or(self.comp_size)))
+    }
+
+    /// Reads all the components of the accessor as numbers, element after element,
+    /// converting integer components to floats (and normalising them if the
+    /// accessor says so).
+    ///
+    /// Unlike `load_data`, this takes the already locked glTF so that it can
+    /// be called while the glTF is being worked on.
+    pub fn read_components(&self, gltf: &LoadedGltf) -> Vec<GltfVecNum> {
+        let multiplicity = self.dimensions.multiplicity();
+        let (data, data_offset, stride) = match self.element_layout(gltf) {
+            Some(layout) => layout,
+            None => return vec![0.0; self.comp_count * multiplicity]
+        };
+        let comp_bytes = self.data_type.size();
+
+        let mut result = Vec::with_capacity(self.comp_count * multiplicity);
+        for i in 0..self.comp_count {
+            let base = data_offset + i * stride;
+            for j in 0..multiplicity {
+                let bytes = component_bytes(data, base + j * comp_bytes, comp_bytes);
+                result.push(read_component(&bytes, self.data_type, self.normalized));
+            }
+        }
+        result
+    }
+
+    /// Reads all the components of an integer accessor without any conversion,
+    /// which is what indices and joints need.
+    pub fn read_integers(&self, gltf: &LoadedGltf) -> Vec<u32> {
+        let multiplicity = self.dimensions.multiplicity();
+        let (data, data_offset, stride) = match self.element_layout(gltf) {
+            Some(layout) => layout,
+            None => return vec![0; self.comp_count * multiplicity]
+        };
+        let comp_bytes = self.data_type.size();
 
+        let mut result = Vec::with_capacity(self.comp_count * multiplicity);
+        for i in 0..self.comp_count {
+            let base = data_offset + i * stride;
+            for j in 0..multiplicity {
+                let bytes = component_bytes(data, base + j * comp_bytes, comp_bytes);
+                result.push(match self.data_type {
+                    DataType::I8 | DataType::U8 => bytes[0] as u32,
+                    DataType::I16 | DataType::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+                    DataType::U32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+                    DataType::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u32
+                });
+            }
+        }
         result
     }
+
+    /// Loads the elements of the accessor. Integer components are converted into
+    /// floats (dequantized if normalised), as `KHR_mesh_quantization` allows.
+    pub fn load_data(&self) -> Vec<LoadedGltfAccessorDatum> {
+        let gltf = self.gltf.lock().unwrap();
+        self.read_components(&gltf)
+            .chunks_exact(self.dimensions.multiplicity())
+            .map(|it| LoadedGltfAccessorDatum::new_from_components(self.dimensions, it))
+            .collect()
+    }
+}
+
+/// Copies the bytes of one accessor component, the bytes beyond the end of the data
+/// being zero (like `load_data_strided` does), so that a truncated buffer or a bad
+/// offset or stride cannot make the reading panic.
+fn component_bytes(data: &[u8], at: usize, comp_bytes: usize) -> [u8; 4] {
+    let start = at.min(data.len());
+    let end = at.saturating_add(comp_bytes).min(data.len());
+    let mut result = [0u8; 4];
+    result[..end - start].copy_from_slice(&data[start..end]);
+    result
+}
+
+/// Reads one little-endian accessor component, applying the normalisation rules
+/// of the glTF specification to normalised integers.
+fn read_component(bytes: &[u8], data_type: DataType, normalized: bool) -> GltfVecNum {
+    match data_type {
+        DataType::I8 => {
+            let value = bytes[0] as i8;
+            if normalized { (value as GltfVecNum / 127.0).max(-1.0) } else { value as GltfVecNum }
+        }
+        DataType::U8 => {
+            let value = bytes[0];
+            if normalized { value as GltfVecNum / 255.0 } else { value as GltfVecNum }
+        }
+        DataType::I16 => {
+            let value = i16::from_le_bytes([bytes[0], bytes[1]]);
+            if normalized { (value as GltfVecNum / 32767.0).max(-1.0) } else { value as GltfVecNum }
+        }
+        DataType::U16 => {
+            let value = u16::from_le_bytes([bytes[0], bytes[1]]);
+            if normalized { value as GltfVecNum / 65535.0 } else { value as GltfVecNum }
+        }
+        DataType::U32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as GltfVecNum,
+        DataType::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
 }
 
 impl LoadedGltfAccessorDatum {
+    /// Builds a datum from the components of one element, matrices being
+    /// stored column-major as in glTF.
+    pub fn new_from_components(dimensions: Dimensions, components: &[GltfVecNum]) -> Self {
+        match dimensions {
+            Dimensions::Scalar => Self::Scalar(components[0]),
+            Dimensions::Vec2 => Self::Vec2(SVector::from_column_slice(components)),
+            Dimensions::Vec3 => Self::Vec3(SVector::from_column_slice(components)),
+            Dimensions::Vec4 => Self::Vec4(SVector::from_column_slice(components)),
+            Dimensions::Mat2 => Self::Mat2(SMatrix::from_column_slice(components)),
+            Dimensions::Mat3 => Self::Mat3(SMatrix::from_column_slice(components)),
+            Dimensions::Mat4 => Self::Mat4(SMatrix::from_column_slice(components))
+        }
+    }
+
     pub fn scalar_of(num: GltfVecNum) -> Self {
         Self::Scalar(num)
     }
@@ -657,6 +641,7 @@ impl LoadedGltfAccessorDatum {
         Self::Mat2(mat)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn mat3_of(
         a00: GltfVecNum, a01: GltfVecNum, a02: GltfVecNum,
         a10: GltfVecNum, a11: GltfVecNum, a12: GltfVecNum,
@@ -669,6 +654,7 @@ impl LoadedGltfAccessorDatum {
         Self::Mat3(mat)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn mat4_of(
         a00: GltfVecNum, a01: GltfVecNum, a02: GltfVecNum, a03: GltfVecNum,
         a10: GltfVecNum, a11: GltfVecNum, a12: GltfVecNum, a13: GltfVecNum,
@@ -707,7 +693,8 @@ impl<'a> LoadedGltfImage<'a> {
             gltf: Arc::clone(gltf),
             index,
             uri,
-            data
+            data,
+            extras: None
         }
     }
 
@@ -715,6 +702,14 @@ impl<'a> LoadedGltfImage<'a> {
         Arc::clone(&self.gltf)
     }
 
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    pub fn set_extras(&mut self, extras: Option<String>) {
+        self.extras = extras
+    }
+
     pub fn index(&self) -> usize {
         self.index
     }
@@ -749,32 +744,52 @@ impl<'a> LoadedGltfSampler<'a> {
             min_filter,
             name,
             wrap_s,
-            wrap_t
+            wrap_t,
+            extras: None
         }
     }
 
+    /// The sampler of the textures which have none: repeating, with linear filters.
+    pub fn new_default(gltf: &Arc<Mutex<LoadedGltf<'a>>>) -> Self {
+        Self::new(
+            gltf,
+            None,
+            Some(MagFilter::Linear),
+            Some(MinFilter::Linear),
+            None,
+            WrappingMode::Repeat,
+            WrappingMode::Repeat)
+    }
+
     pub fn new_from_sampler(
         gltf: &Arc<Mutex<LoadedGltf<'a>>>,
         sampler: &texture::Sampler
     ) -> Self {
-        let name = match sampler.name() {
-            Some(str) => Some(String::from(str)),
-            None => None
-        };
-        Self::new(
+        let name = sampler.name().map(String::from);
+        let mut result = Self::new(
             gltf,
             sampler.index(),
             sampler.mag_filter(),
             sampler.min_filter(),
             name,
             sampler.wrap_s(),
-            sampler.wrap_t())
+            sampler.wrap_t());
+        result.extras = extras_to_string(sampler.extras());
+        result
     }
 
     pub fn gltf(&self) -> Arc<Mutex<LoadedGltf<'a>>> {
         Arc::clone(&self.gltf)
     }
 
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    pub fn set_extras(&mut self, extras: Option<String>) {
+        self.extras = extras
+    }
+
     /// Returns None if the sampler is the default one within the glTF.
     pub fn index(&self) -> Option<usize> {
         self.index
@@ -815,20 +830,37 @@ impl<'a> LoadedGltfSampler<'a> {
     pub fn wrap_t_gl(&self) -> u32 {
         wrapping_mode_to_gl_value(self.wrap_t)
     }
+
+    /// Returns the OpenGL parameters to set up a texture with: the magnification filter,
+    /// the minification filter and the S and T wrapping modes.
+    ///
+    /// The spec leaves undefined filters to the renderer; they default to linear filtering,
+    /// between the mip levels too for minification.
+    pub fn gl_parameters(&self) -> [u32; 4] {
+        [
+            self.mag_filter_gl().unwrap_or(constants::opengl::GL_LINEAR),
+            self.min_filter_gl().unwrap_or(constants::opengl::GL_LINEAR_MIPMAP_LINEAR),
+            self.wrap_s_gl(),
+            self.wrap_t_gl()
+        ]
+    }
 }
 
 impl<'a> LoadedGltfTexture<'a> {
     pub fn new(
         gltf: &Arc<Mutex<LoadedGltf<'a>>>,
         index: usize,
-        source_index: usize,
-        sampler_index: usize
+        source_index: Option<usize>,
+        fallback_source_index: Option<usize>,
+        sampler_index: Option<usize>
     ) -> Self {
         Self {
             gltf: Arc::clone(gltf),
             index,
             source_index,
-            sampler_index
+            fallback_source_index,
+            sampler_index,
+            extras: None
         }
     }
 
@@ -836,33 +868,71 @@ impl<'a> LoadedGltfTexture<'a> {
         gltf: &Arc<Mutex<LoadedGltf<'a>>>,
         texture: &texture::Texture
     ) -> Self {
-        Self::new(
+        let core_source_index = texture.source().map(|it| it.index());
+        let extension_source_index = TEXTURE_SOURCE_EXTENSIONS.iter()
+            .filter(|(_, format)| util::image::is_format_decodable(*format))
+            .find_map(|(name, _)| texture.extension_value(name)
+                .and_then(|it| it.get("source"))
+                .and_then(Value::as_u64)
+                .map(|it| it as usize));
+        let (source_index, fallback_source_index) = match extension_source_index {
+            Some(index) => (Some(index), core_source_index),
+            None => (core_source_index, None)
+        };
+        let mut result = Self::new(
             gltf,
             texture.index(),
-            texture.source().index(),
-            // We unwrap here because we are sure the sampler has its index
-            // as it has been specified by this texture.
-            texture.sampler().index().unwrap())
+            source_index,
+            fallback_source_index,
+            texture.sampler().index());
+        result.extras = extras_to_string(texture.extras());
+        result
     }
 
     pub fn gltf(&self) -> Arc<Mutex<LoadedGltf<'a>>> {
         Arc::clone(&self.gltf)
     }
 
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    pub fn set_extras(&mut self, extras: Option<String>) {
+        self.extras = extras
+    }
+
     pub fn index(&self) -> usize {
         self.index
     }
 
-    pub fn source_index(&self) -> usize {
+    pub fn source_index(&self) -> Option<usize> {
         self.source_index
     }
 
-    pub fn sampler_index(&self) -> usize {
+    pub fn fallback_source_index(&self) -> Option<usize> {
+        self.fallback_source_index
+    }
+
+    /// Returns None if the texture uses the default sampler.
+    pub fn sampler_index(&self) -> Option<usize> {
         self.sampler_index
     }
 }
 
+/// The texture extensions naming an image of another format than PNG and JPEG,
+/// in the order of preference.
+pub const TEXTURE_SOURCE_EXTENSIONS: [(&str, NativeImageFormat); 3] = [
+    ("KHR_texture_basisu", NativeImageFormat::Ktx2),
+    ("EXT_texture_webp", NativeImageFormat::Image(ImageFormat::WebP)),
+    ("EXT_texture_avif", NativeImageFormat::Image(ImageFormat::Avif))
+];
+
+fn texture_transform_of(json: Option<&Value>) -> Option<material::TextureTransformInfo> {
+    json.map(material::TextureTransformInfo::new_from_json)
+}
+
 impl<'a> LoadedGltfMaterial<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         gltf: &Arc<Mutex<LoadedGltf<'a>>>,
         index: Option<usize>,
@@ -870,7 +940,14 @@ impl<'a> LoadedGltfMaterial<'a> {
         normal_texture: Option<material::NormalTextureInfo>,
         occlusion_texture: Option<material::OcclusionTextureInfo>,
         emissive_texture: Option<material::EmissiveTextureInfo>,
-        emissive_factor: material::EmissiveFactorInfo
+        emissive_factor: material::EmissiveFactorInfo,
+        alpha_mode: AlphaMode,
+        alpha_cutoff: GltfVecNum,
+        double_sided: bool,
+        name: Option<String>,
+        unlit: bool,
+        extensions: HashMap<String, Box<dyn Any + Send + Sync>>,
+        unhandled_extensions: Vec<String>
     ) -> Self {
         Self {
             gltf: Arc::clone(gltf),
@@ -879,33 +956,63 @@ impl<'a> LoadedGltfMaterial<'a> {
             normal_texture,
             occlusion_texture,
             emissive_texture,
-            emissive_factor
+            emissive_factor,
+            alpha_mode,
+            alpha_cutoff,
+            double_sided,
+            name,
+            unlit,
+            extensions,
+            unhandled_extensions,
+            extras: None
         }
     }
 
+    /// The material of the primitives which have none, as the glTF specification defines it:
+    /// an opaque, single-sided white material with metallic and roughness factors of 1.
+    pub fn new_default(gltf: &Arc<Mutex<LoadedGltf<'a>>>) -> Self {
+        Self::new(
+            gltf,
+            None,
+            material::PbrMetallicRoughnessInfo::new(None, Vector4::new(1.0, 1.0, 1.0, 1.0), None, 1.0, 1.0),
+            None,
+            None,
+            None,
+            Vector3::zeros(),
+            AlphaMode::Opaque,
+            material::DEFAULT_ALPHA_CUTOFF,
+            false,
+            None,
+            false,
+            HashMap::new(),
+            Vec::new())
+    }
+
     pub fn new_from_material(
         gltf: &Arc<Mutex<LoadedGltf<'a>>>,
         material: &Material
     ) -> Self {
         let pbrmr = material.pbr_metallic_roughness();
-        let base_color_texture;
-        let metallic_roughness_texture;
-        match pbrmr.base_color_texture() {
+        let base_color_texture = match pbrmr.base_color_texture() {
             Some(texture) => {
-                let info = material::PbrMetallicRoughnessTextureInfo::new(
+                let mut info = material::PbrMetallicRoughnessTextureInfo::new(
                     texture.texture().index(), texture.tex_coord() as usize);
-                base_color_texture = Some(info);
+                info.set_texture_transform(texture_transform_of(texture.extension_value(
+                    material::TEXTURE_TRANSFORM_EXTENSION)));
+                Some(info)
             }
-            None => base_color_texture = None
-        }
-        match pbrmr.metallic_roughness_texture() {
+            None => None
+        };
+        let metallic_roughness_texture = match pbrmr.metallic_roughness_texture() {
             Some(texture) => {
-                let info = material::PbrMetallicRoughnessTextureInfo::new(
+                let mut info = material::PbrMetallicRoughnessTextureInfo::new(
                     texture.texture().index(), texture.tex_coord() as usize);
-                metallic_roughness_texture = Some(info);
+                info.set_texture_transform(texture_transform_of(texture.extension_value(
+                    material::TEXTURE_TRANSFORM_EXTENSION)));
+                Some(info)
             }
-            None => metallic_roughness_texture = None
-        }
+            None => None
+        };
         let arr = pbrmr.base_color_factor();
         let base_color_factor = Vector4::new(arr[0], arr[1], arr[2], arr[3]);
         let metallic_factor = pbrmr.metallic_factor();
@@ -917,45 +1024,79 @@ impl<'a> LoadedGltfMaterial<'a> {
             metallic_factor,
             roughness_factor);
 
-        let normal_texture;
-        match material.normal_texture() {
+        let normal_texture = match material.normal_texture() {
             Some(texture) => {
-                let info = material::NormalTextureInfo::new(
+                let mut info = material::NormalTextureInfo::new(
                     texture.scale(), texture.texture().index(), texture.tex_coord() as usize);
-                normal_texture = Some(info);
+                info.set_texture_transform(texture_transform_of(texture.extension_value(
+                    material::TEXTURE_TRANSFORM_EXTENSION)));
+                Some(info)
             }
-            None => normal_texture = None
-        }
+            None => None
+        };
 
-        let occlusion_texture;
-        match material.occlusion_texture() {
+        let occlusion_texture = match material.occlusion_texture() {
             Some(texture) => {
-                let info = material::OcclusionTextureInfo::new(texture.strength(), texture.texture().index(), texture.tex_coord() as usize);
-                occlusion_texture = Some(info);
+                let mut info = material::OcclusionTextureInfo::new(texture.strength(), texture.texture().index(), texture.tex_coord() as usize);
+                info.set_texture_transform(texture_transform_of(texture.extension_value(
+                    material::TEXTURE_TRANSFORM_EXTENSION)));
+                Some(info)
             }
-            None => occlusion_texture = None
-        }
+            None => None
+        };
 
-        let emissive_texture;
-        match material.emissive_texture() {
+        let emissive_texture = match material.emissive_texture() {
             Some(texture) => {
-                let info = material::EmissiveTextureInfo::new(texture.texture().index(), texture.tex_coord() as usize);
-                emissive_texture = Some(info);
+                let mut info = material::EmissiveTextureInfo::new(texture.texture().index(), texture.tex_coord() as usize);
+                info.set_texture_transform(texture_transform_of(texture.extension_value(
+                    material::TEXTURE_TRANSFORM_EXTENSION)));
+                Some(info)
             }
-            None => emissive_texture = None
-        }
+            None => None
+        };
 
         let arr = material.emissive_factor();
         let emissive_factor = Vector3::new(arr[0], arr[1], arr[2]);
 
-        Self::new(
+        let alpha_cutoff = material.alpha_cutoff().unwrap_or(material::DEFAULT_ALPHA_CUTOFF);
+        let name = material.name().map(String::from);
+        let unlit = material.extension_value(material::UNLIT_EXTENSION).is_some();
+
+        let mut extensions = HashMap::new();
+        let mut unhandled_extensions = Vec::new();
+        if let Some(material_extensions) = material.extensions() {
+            let registry = material_extension::MATERIAL_EXTENSION_REGISTRY.lock().unwrap();
+            for (name, json) in material_extensions {
+                match registry.handler(name) {
+                    Some(handler) => {
+                        extensions.insert(name.clone(), handler.parse(json));
+                    }
+                    None => {
+                        if !registry.is_handled(name) {
+                            unhandled_extensions.push(name.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut result = Self::new(
             gltf,
             material.index(),
             pbr_metallic_roughness,
             normal_texture,
             occlusion_texture,
             emissive_texture,
-            emissive_factor)
+            emissive_factor,
+            material.alpha_mode(),
+            alpha_cutoff,
+            material.double_sided(),
+            name,
+            unlit,
+            extensions,
+            unhandled_extensions);
+        result.extras = extras_to_string(material.extras());
+        result
     }
 
 
@@ -963,6 +1104,14 @@ impl<'a> LoadedGltfMaterial<'a> {
         &self.gltf
     }
 
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    pub fn set_extras(&mut self, extras: Option<String>) {
+        self.extras = extras
+    }
+
     pub fn index(&self) -> Option<usize> {
         self.index
     }
@@ -986,6 +1135,150 @@ impl<'a> LoadedGltfMaterial<'a> {
     pub fn emissive_factor(&self) -> material::EmissiveFactorInfo {
         self.emissive_factor
     }
+
+    /// Returns the animatable factors of the material, with the values the animation state
+    /// wrote over them; the values of the glTF without a state.
+    pub fn factors(&self, state: Option<&AnimationState>) -> material::MaterialFactors {
+        let mut factors = material::MaterialFactors {
+            base_color_factor: self.pbr_metallic_roughness.base_color_factor(),
+            metallic_factor: self.pbr_metallic_roughness.metallic_factor(),
+            roughness_factor: self.pbr_metallic_roughness.roughness_factor(),
+            emissive_factor: self.emissive_factor,
+            emissive_strength: self.extension::<material_extension::EmissiveStrength>()
+                .map(|it| it.emissive_strength)
+                .unwrap_or(1.0),
+            alpha_cutoff: self.alpha_cutoff,
+            normal_scale: self.normal_texture.as_ref().map(|it| it.scale()).unwrap_or(1.0),
+            occlusion_strength: self.occlusion_texture.as_ref().map(|it| it.strength()).unwrap_or(1.0)
+        };
+        if let (Some(state), Some(index)) = (state, self.index) {
+            state.apply_to_material_factors(index, &mut factors);
+        }
+        factors
+    }
+
+    /// Returns the texture reference of the slot with the texture transform the animation
+    /// state animated; the one of the glTF without a state.
+    pub fn animated_texture_reference(
+        &self,
+        slot: material::TextureSlot,
+        state: Option<&AnimationState>
+    ) -> Option<material::TextureReference> {
+        let reference = self.texture_reference(slot)?;
+        match (state, self.index) {
+            (Some(state), Some(index)) => Some(material::TextureReference::new(
+                reference.texture_index(),
+                reference.tex_coord(),
+                state.texture_transform(index, slot, reference.texture_transform()))),
+            _ => Some(reference)
+        }
+    }
+
+    pub fn texture_reference(&self, slot: material::TextureSlot) -> Option<material::TextureReference> {
+        match slot {
+            material::TextureSlot::BaseColor =>
+                self.pbr_metallic_roughness.base_color_texture().as_ref().map(|it| it.reference()),
+            material::TextureSlot::MetallicRoughness =>
+                self.pbr_metallic_roughness.metallic_roughness_texture().as_ref().map(|it| it.reference()),
+            material::TextureSlot::Normal => self.normal_texture.as_ref().map(|it| it.reference()),
+            material::TextureSlot::Occlusion => self.occlusion_texture.as_ref().map(|it| it.reference()),
+            material::TextureSlot::Emissive => self.emissive_texture.as_ref().map(|it| it.reference())
+        }
+    }
+
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    pub fn alpha_cutoff(&self) -> GltfVecNum {
+        self.alpha_cutoff
+    }
+
+    pub fn double_sided(&self) -> bool {
+        self.double_sided
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn unlit(&self) -> bool {
+        self.unlit
+    }
+
+    /// Returns the typed data of the extension, None if the material lacks it.
+    pub fn extension<T: material_extension::MaterialExtensionData>(&self) -> Option<&T> {
+        self.extensions.get(T::NAME)?.downcast_ref::<T>()
+    }
+
+    /// Returns the data of an extension parsed by a custom handler.
+    pub fn extension_data(&self, name: &str) -> Option<&(dyn Any + Send + Sync)> {
+        self.extensions.get(name).map(|it| it.as_ref())
+    }
+
+    pub fn unhandled_extensions(&self) -> &Vec<String> {
+        &self.unhandled_extensions
+    }
+
+    /// Whether the material gives off light in the animation state, its emissive factor
+    /// scaled by `KHR_materials_emissive_strength` being non-zero.
+    pub fn is_emissive(&self, state: Option<&AnimationState>) -> bool {
+        self.factors(state).emission().max() > 0.0
+    }
+
+    /// Classifies the material into the kind of `RenderType` its primitives need:
+    ///
+    /// - BLEND materials are translucent, or additive if they are unlit and emissive,
+    ///   unless their base colour is fully opaque anyway.
+    /// - Unlit emissive materials, whose colour is meant to be the emission, are emissive
+    ///   and bind their emissive texture.
+    /// - Otherwise OPAQUE materials are solid and MASK materials are cutout,
+    ///   both without culling if the material is double-sided.
+    ///
+    /// Lit emissive materials keep their base colour, lighting and alpha test: their
+    /// emission is reported as a second, emissive layer to draw over them.
+    ///
+    /// The factors are the ones of the animation state, if any.
+    pub fn render_class(&self, unlit_full_bright: bool, state: Option<&AnimationState>) -> material::RenderClass {
+        let base_color_texture = self.pbr_metallic_roughness.base_color_texture()
+            .as_ref().map(|it| it.index());
+        let emissive_texture = self.emissive_texture.as_ref().map(|it| it.index());
+        let factors = self.factors(state);
+        let opaque_base_color = base_color_texture.is_none() && factors.base_color_factor[3] >= 1.0;
+        let emissive = factors.emission().max() > 0.0;
+
+        let category = match self.alpha_mode {
+            AlphaMode::Blend if !opaque_base_color => if self.unlit && emissive {
+                material::RenderCategory::Additive
+            } else {
+                material::RenderCategory::Translucent
+            },
+            _ if emissive && self.unlit => material::RenderCategory::Emissive,
+            _ if self.double_sided => material::RenderCategory::CutoutNoCull,
+            AlphaMode::Mask => material::RenderCategory::Cutout,
+            _ => material::RenderCategory::Solid
+        };
+        let (texture_index, emissive_layer) = match category {
+            material::RenderCategory::Emissive | material::RenderCategory::Additive =>
+                (emissive_texture.or(base_color_texture), false),
+            _ => (base_color_texture, emissive)
+        };
+        let mut result = material::RenderClass::new(category, self.shading_hint(unlit_full_bright), texture_index);
+        if emissive_layer {
+            result.set_emissive_layer(true, emissive_texture);
+        }
+        result
+    }
+
+    /// Unlit materials skip the diffuse lighting, either keeping the vanilla entity
+    /// lighting or going full-bright as asked.
+    pub fn shading_hint(&self, unlit_full_bright: bool) -> material::ShadingHint {
+        match (self.unlit, unlit_full_bright) {
+            (false, _) => material::ShadingHint::Lit,
+            (true, false) => material::ShadingHint::VanillaEntityLighting,
+            (true, true) => material::ShadingHint::FullBright
+        }
+    }
 }
 
 impl<'a> LoadedGltfMesh<'a> {
@@ -999,7 +1292,8 @@ impl<'a> LoadedGltfMesh<'a> {
             gltf: Arc::clone(gltf),
             index,
             primitives,
-            weights
+            weights,
+            extras: None
         }
     }
 
@@ -1024,16 +1318,26 @@ impl<'a> LoadedGltfMesh<'a> {
         let mut weights_info = Vec::new();
         if let Some(weights) = mesh.weights() {
             weights.iter().for_each(|it| {
-                weights_info.push(it.clone());
+                weights_info.push(*it);
             });
         };
-        Self::new(gltf, index, primitives, weights_info)
+        let mut result = Self::new(gltf, index, primitives, weights_info);
+        result.extras = extras_to_string(mesh.extras());
+        result
     }
 
     pub fn gltf(&self) -> &Arc<Mutex<LoadedGltf<'a>>> {
         &self.gltf
     }
 
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    pub fn set_extras(&mut self, extras: Option<String>) {
+        self.extras = extras
+    }
+
     pub fn index(&self) -> usize {
         self.index
     }
@@ -1047,17 +1351,150 @@ impl<'a> LoadedGltfMesh<'a> {
     }
 }
 
+impl<'a> LoadedGltfNode<'a> {
+    pub fn new(
+        gltf: &Arc<Mutex<LoadedGltf<'a>>>,
+        index: usize,
+        name: Option<String>,
+        children: Vec<usize>,
+        mesh_index: Option<usize>,
+        light_index: Option<usize>,
+        transform: node::TransformInfo
+    ) -> Self {
+        Self {
+            gltf: Arc::clone(gltf),
+            index,
+            name,
+            parent_index: None,
+            children,
+            mesh_index,
+            light_index,
+            transform,
+            instances: Vec::new(),
+            extras: None
+        }
+    }
+
+    pub fn new_from_node(
+        gltf: &Arc<Mutex<LoadedGltf<'a>>>,
+        node: &Node
+    ) -> Self {
+        let name = node.name().map(String::from);
+        let children = node.children().map(|it| it.index()).collect();
+        let mesh_index = node.mesh().map(|it| it.index());
+        let light_index = light::node_light_index(node);
+        let transform = node::TransformInfo::new_from_transform(node.transform());
+        let mut result = Self::new(gltf, node.index(), name, children, mesh_index, light_index, transform);
+        result.extras = extras_to_string(node.extras());
+        result
+    }
+
+    pub fn gltf(&self) -> &Arc<Mutex<LoadedGltf<'a>>> {
+        &self.gltf
+    }
+
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+
+    pub fn set_extras(&mut self, extras: Option<String>) {
+        self.extras = extras
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    /// Returns None if the node is a root node of the scene hierarchy.
+    pub fn parent_index(&self) -> Option<usize> {
+        self.parent_index
+    }
+
+    pub fn children(&self) -> &Vec<usize> {
+        &self.children
+    }
+
+    pub fn mesh_index(&self) -> Option<usize> {
+        self.mesh_index
+    }
+
+    pub fn light_index(&self) -> Option<usize> {
+        self.light_index
+    }
+
+    pub fn instances(&self) -> &Vec<node::TransformInfo> {
+        &self.instances
+    }
+
+    pub fn set_instances(&mut self, instances: Vec<node::TransformInfo>) {
+        self.instances = instances
+    }
+
+    /// Returns the rest transform of the node, as written in the glTF.
+    pub fn transform(&self) -> node::TransformInfo {
+        self.transform
+    }
+}
+
+impl Default for LoadedGltf<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> LoadedGltf<'a> {
     pub fn new() -> Self {
         Self {
+            asset: asset::AssetInfo::default(),
             buffers: Vec::new(),
             buffer_views: Vec::new(),
             accessors: Vec::new(),
             images: Vec::new(),
             samplers: Vec::new(),
+            default_sampler: None,
             textures: Vec::new(),
             materials: Vec::new(),
-            meshes: Vec::new()
+            default_material: None,
+            meshes: Vec::new(),
+            nodes: Vec::new(),
+            lights: Vec::new(),
+            variants: Vec::new(),
+            animations: Vec::new(),
+            scenes: Vec::new(),
+            default_scene_index: None,
+            constraints: ConstraintSet::new(),
+            unlit_full_bright: false
+        }
+    }
+
+    pub fn asset(&self) -> &asset::AssetInfo {
+        &self.asset
+    }
+
+    pub fn set_asset(&mut self, asset: asset::AssetInfo) {
+        self.asset = asset
+    }
+
+    /// Returns the `extras` of the object as JSON text, or None if there is no such object.
+    /// The inner None means the object has no `extras`.
+    pub fn object_extras(&self, kind: asset::ObjectKind, index: usize) -> Option<Option<&String>> {
+        match kind {
+            asset::ObjectKind::Buffer => self.buffers.get(index).map(|it| it.extras()),
+            asset::ObjectKind::BufferView => self.buffer_views.get(index).map(|it| it.extras()),
+            asset::ObjectKind::Accessor => self.accessors.get(index).map(|it| it.extras()),
+            // Only the images loaded from a URI are kept, so they are not in index order.
+            asset::ObjectKind::Image => self.images.iter().find(|it| it.index() == index).map(|it| it.extras()),
+            asset::ObjectKind::Sampler => self.samplers.get(index).map(|it| it.extras()),
+            asset::ObjectKind::Texture => self.textures.get(index).map(|it| it.extras()),
+            asset::ObjectKind::Material => self.materials.get(index).map(|it| it.extras()),
+            asset::ObjectKind::Mesh => self.meshes.get(index).map(|it| it.extras()),
+            asset::ObjectKind::Node => self.nodes.get(index).map(|it| it.extras()),
+            asset::ObjectKind::Animation => self.animations.get(index).map(|it| it.extras()),
+            asset::ObjectKind::Light => self.lights.get(index).map(|it| it.extras())
         }
     }
 
@@ -1101,6 +1538,71 @@ impl<'a> LoadedGltf<'a> {
         &mut self.samplers
     }
 
+    pub fn default_sampler(&self) -> Option<&LoadedGltfSampler<'a>> {
+        self.default_sampler.as_ref()
+    }
+
+    /// Returns the sampler of the texture, the default sampler if it has none.
+    /// Returns None only if there is no such texture.
+    pub fn texture_sampler(&self, texture_index: usize) -> Option<&LoadedGltfSampler<'a>> {
+        match self.textures.get(texture_index)?.sampler_index() {
+            Some(sampler_index) => self.samplers.get(sampler_index).or(self.default_sampler.as_ref()),
+            None => self.default_sampler.as_ref()
+        }
+    }
+
+    /// Returns the sampler of the first texture sampling the image loaded from the URI (as its
+    /// source or as its fallback one), the default sampler if no texture samples it.
+    pub fn image_sampler(&self, uri: &str) -> Option<&LoadedGltfSampler<'a>> {
+        let image_index = self.images.iter().find(|it| it.uri().as_str() == uri)?.index();
+        match self.textures.iter().position(|it| it.source_index() == Some(image_index)
+            || it.fallback_source_index() == Some(image_index)) {
+            Some(texture_index) => self.texture_sampler(texture_index),
+            None => self.default_sampler.as_ref()
+        }
+    }
+
+    /// Returns how the materials use the image loaded from the URI, over every texture sampling it
+    /// (as its source or as its fallback one). An image no material uses counts as a colour one.
+    /// Returns None if no image is loaded from the URI.
+    pub fn image_usage(&self, uri: &str) -> Option<material::ImageUsage> {
+        let image_index = self.images.iter().find(|it| it.uri().as_str() == uri)?.index();
+        let samples_image = |reference: &material::TextureReference| {
+            self.textures.get(reference.texture_index()).is_some_and(|it|
+                it.source_index() == Some(image_index) || it.fallback_source_index() == Some(image_index))
+        };
+        let mut used = false;
+        let mut color = false;
+        let mut alpha_cutoff: Option<GltfVecNum> = None;
+        for material in &self.materials {
+            for slot in [
+                material::TextureSlot::BaseColor,
+                material::TextureSlot::MetallicRoughness,
+                material::TextureSlot::Normal,
+                material::TextureSlot::Occlusion,
+                material::TextureSlot::Emissive
+            ] {
+                if !material.texture_reference(slot).is_some_and(|it| samples_image(&it)) {
+                    continue;
+                }
+                used = true;
+                match slot {
+                    material::TextureSlot::BaseColor => {
+                        color = true;
+                        if material.alpha_mode() == AlphaMode::Mask {
+                            // Several cutoffs cannot all be kept; the lowest keeps the most texels.
+                            let cutoff = material.alpha_cutoff();
+                            alpha_cutoff = Some(alpha_cutoff.map_or(cutoff, |it| it.min(cutoff)));
+                        }
+                    }
+                    material::TextureSlot::Emissive => color = true,
+                    _ => {}
+                }
+            }
+        }
+        Some(material::ImageUsage::new(color || !used, alpha_cutoff))
+    }
+
     pub fn textures(&self) -> &Vec<LoadedGltfTexture<'a>> {
         &self.textures
     }
@@ -1117,6 +1619,22 @@ impl<'a> LoadedGltf<'a> {
         &mut self.materials
     }
 
+    pub fn default_material(&self) -> Option<&LoadedGltfMaterial<'a>> {
+        self.default_material.as_ref()
+    }
+
+    /// Returns the material of the primitive for the variant (see
+    /// `primitive_material_for_variant`), the default material if it has none.
+    pub fn primitive_material(
+        &self,
+        primitive: &mesh::PrimitiveInfo,
+        variant_name: Option<&str>
+    ) -> Option<&LoadedGltfMaterial<'a>> {
+        self.primitive_material_for_variant(primitive, variant_name)
+            .and_then(|it| self.materials.get(it))
+            .or(self.default_material.as_ref())
+    }
+
     pub fn meshes(&self) -> &Vec<LoadedGltfMesh<'a>> {
         &self.meshes
     }
@@ -1124,13 +1642,303 @@ impl<'a> LoadedGltf<'a> {
     pub fn meshes_mut(&mut self) -> &mut Vec<LoadedGltfMesh<'a>> {
         &mut self.meshes
     }
+
+    pub fn primitive(&self, mesh_index: usize, primitive_index: usize) -> Option<&mesh::PrimitiveInfo> {
+        self.meshes.get(mesh_index)?.primitives.get(primitive_index)
+    }
+
+    /// Decodes an attribute of the primitive into its components, element after element.
+    pub fn read_attribute(
+        &self,
+        primitive: &mesh::PrimitiveInfo,
+        semantic: &Semantic
+    ) -> Option<Vec<GltfVecNum>> {
+        let accessor = self.accessors.get(primitive.attribute_accessor(semantic)?)?;
+        Some(accessor.read_components(self))
+    }
+
+    /// Decodes the POSITION attribute of the primitive. Quantized positions are
+    /// returned as integers converted to floats: the dequantization scale and
+    /// offset are carried by the transform of the node instancing the mesh.
+    pub fn read_positions(&self, primitive: &mesh::PrimitiveInfo) -> Option<Vec<Vector3<GltfVecNum>>> {
+        let components = self.read_attribute(primitive, &Semantic::Positions)?;
+        Some(components.chunks_exact(3).map(|it| Vector3::new(it[0], it[1], it[2])).collect())
+    }
+
+    /// Decodes the NORMAL attribute of the primitive, renormalising the normals
+    /// as quantized ones lose their unit length.
+    pub fn read_normals(&self, primitive: &mesh::PrimitiveInfo) -> Option<Vec<Vector3<GltfVecNum>>> {
+        let components = self.read_attribute(primitive, &Semantic::Normals)?;
+        Some(components.chunks_exact(3)
+            .map(|it| {
+                let normal = Vector3::new(it[0], it[1], it[2]);
+                normal.try_normalize(GltfVecNum::EPSILON).unwrap_or(normal)
+            })
+            .collect())
+    }
+
+    /// Decodes the TEXCOORD_n set of the primitive. If a texture transform is
+    /// given, it is baked into the returned coordinates.
+    pub fn read_tex_coords(
+        &self,
+        primitive: &mesh::PrimitiveInfo,
+        set: usize,
+        texture_transform: Option<material::TextureTransformInfo>
+    ) -> Option<Vec<Vector2<GltfVecNum>>> {
+        let components = self.read_attribute(primitive, &Semantic::TexCoords(set as u32))?;
+        let tex_coords = components.chunks_exact(2).map(|it| Vector2::new(it[0], it[1]));
+        Some(match texture_transform {
+            Some(transform) => tex_coords.map(|it| transform.apply(it)).collect(),
+            None => tex_coords.collect()
+        })
+    }
+
+    /// Decodes the COLOR_n set of the primitive into linear RGBA, RGB colours getting
+    /// an alpha of 1. Normalised integer colours are converted to floats.
+    ///
+    /// If `multiply_base_color` is true, the colours are multiplied by the base colour
+    /// factor of the primitive's material (as animated in the state, if any), as the glTF
+    /// shading model does, and if `to_srgb` is true, they are converted into sRGB (keeping
+    /// alpha linear), which is what the vertex colour input of Minecraft expects.
+    pub fn read_colors(
+        &self,
+        primitive: &mesh::PrimitiveInfo,
+        set: usize,
+        to_srgb: bool,
+        multiply_base_color: bool,
+        state: Option<&AnimationState>
+    ) -> Option<Vec<Vector4<GltfVecNum>>> {
+        let accessor = self.accessors.get(primitive.attribute_accessor(&Semantic::Colors(set as u32))?)?;
+        let components = accessor.read_components(self);
+        let factor = match self.primitive_material(primitive, None) {
+            Some(material) if multiply_base_color => material.factors(state).base_color_factor,
+            _ => Vector4::new(1.0, 1.0, 1.0, 1.0)
+        };
+        let colors = match accessor.dimensions() {
+            Dimensions::Vec3 => components.chunks_exact(3)
+                .map(|it| Vector4::new(it[0], it[1], it[2], 1.0))
+                .collect::<Vec<_>>(),
+            _ => components.chunks_exact(4)
+                .map(|it| Vector4::new(it[0], it[1], it[2], it[3]))
+                .collect()
+        };
+        Some(colors.into_iter().map(|it| {
+            let color = it.component_mul(&factor);
+            if to_srgb {
+                Vector4::new(
+                    util::image::linear_to_srgb(color.x.clamp(0.0, 1.0)),
+                    util::image::linear_to_srgb(color.y.clamp(0.0, 1.0)),
+                    util::image::linear_to_srgb(color.z.clamp(0.0, 1.0)),
+                    color.w)
+            } else {
+                color
+            }
+        }).collect())
+    }
+
+    pub fn nodes(&self) -> &Vec<LoadedGltfNode<'a>> {
+        &self.nodes
+    }
+
+    pub fn nodes_mut(&mut self) -> &mut Vec<LoadedGltfNode<'a>> {
+        &mut self.nodes
+    }
+
+    pub fn node_index_by_name(&self, name: &str) -> Option<usize> {
+        self.nodes.iter()
+            .find(|it| it.name.as_deref() == Some(name))
+            .map(|it| it.index)
+    }
+
+    /// Fills in the parent index of every node from the children lists.
+    /// This must be called once all the nodes have been loaded.
+    pub fn link_node_parents(&mut self) {
+        let mut parents = vec![None; self.nodes.len()];
+        for node in &self.nodes {
+            for child in &node.children {
+                if let Some(parent) = parents.get_mut(*child) {
+                    *parent = Some(node.index);
+                }
+            }
+        }
+        for (node, parent) in self.nodes.iter_mut().zip(parents) {
+            node.parent_index = parent;
+        }
+    }
+
+    pub fn unlit_full_bright(&self) -> bool {
+        self.unlit_full_bright
+    }
+
+    pub fn set_unlit_full_bright(&mut self, unlit_full_bright: bool) {
+        self.unlit_full_bright = unlit_full_bright
+    }
+
+    /// Returns the shading hint of the primitive's material, the default material
+    /// being lit.
+    pub fn primitive_shading_hint(&self, primitive: &mesh::PrimitiveInfo) -> material::ShadingHint {
+        self.primitive_material(primitive, None)
+            .map(|it| it.shading_hint(self.unlit_full_bright))
+            .unwrap_or(material::ShadingHint::Lit)
+    }
+
+    /// Returns the render class of the primitive's material for the variant (see
+    /// `primitive_material`) in the animation state, if any. The default material
+    /// is solid and untextured.
+    pub fn primitive_render_class(
+        &self,
+        primitive: &mesh::PrimitiveInfo,
+        variant_name: Option<&str>,
+        state: Option<&AnimationState>
+    ) -> material::RenderClass {
+        self.primitive_material(primitive, variant_name)
+            .map(|it| it.render_class(self.unlit_full_bright, state))
+            .unwrap_or_else(|| material::RenderClass::new(
+                material::RenderCategory::Solid, material::ShadingHint::Lit, None))
+    }
+
+    /// Returns the URI of the image the texture is sampled from, None if the texture
+    /// has no source or its image is not loaded from a URI.
+    pub fn texture_image_uri(&self, texture_index: usize) -> Option<&String> {
+        let source_index = self.textures.get(texture_index)?.source_index()?;
+        self.images.iter().find(|it| it.index() == source_index).map(|it| it.uri())
+    }
+
+    /// Returns the names of the material extensions nothing handled, over all materials.
+    pub fn unhandled_material_extensions(&self) -> Vec<String> {
+        let mut result: Vec<String> = Vec::new();
+        for material in &self.materials {
+            for name in &material.unhandled_extensions {
+                if !result.contains(name) {
+                    result.push(name.clone());
+                }
+            }
+        }
+        result
+    }
+
+    pub fn scenes(&self) -> &Vec<scene::SceneInfo> {
+        &self.scenes
+    }
+
+    pub fn scenes_mut(&mut self) -> &mut Vec<scene::SceneInfo> {
+        &mut self.scenes
+    }
+
+    pub fn default_scene_index(&self) -> Option<usize> {
+        self.default_scene_index
+    }
+
+    pub fn set_default_scene_index(&mut self, default_scene_index: Option<usize>) {
+        self.default_scene_index = default_scene_index
+    }
+
+    /// Returns the scene to draw: the `scene` of the glTF, or its first scene
+    /// if it names none. None if the glTF has no scene.
+    pub fn default_scene(&self) -> Option<&scene::SceneInfo> {
+        match self.default_scene_index {
+            Some(index) => self.scenes.get(index),
+            None => self.scenes.first()
+        }
+    }
+
+    /// Returns the root nodes of the default scene. A glTF without any scene
+    /// is taken to draw all its parentless nodes, so that such files still show up.
+    pub fn root_nodes(&self) -> Vec<usize> {
+        match self.default_scene() {
+            Some(scene) => scene.root_nodes().iter()
+                .copied()
+                .filter(|it| *it < self.nodes.len())
+                .collect(),
+            None if self.scenes.is_empty() => self.nodes.iter()
+                .filter(|it| it.parent_index().is_none())
+                .map(|it| it.index())
+                .collect(),
+            None => Vec::new()
+        }
+    }
+
+    pub fn lights(&self) -> &Vec<light::LightInfo> {
+        &self.lights
+    }
+
+    pub fn lights_mut(&mut self) -> &mut Vec<light::LightInfo> {
+        &mut self.lights
+    }
+
+    pub fn variants(&self) -> &Vec<String> {
+        &self.variants
+    }
+
+    pub fn variants_mut(&mut self) -> &mut Vec<String> {
+        &mut self.variants
+    }
+
+    pub fn variant_index_by_name(&self, name: &str) -> Option<usize> {
+        self.variants.iter().position(|it| it == name)
+    }
+
+    /// Resolves the material of the primitive for the named variant. With no
+    /// variant, or a variant unknown to the glTF, the default material is used.
+    pub fn primitive_material_for_variant(
+        &self,
+        primitive: &mesh::PrimitiveInfo,
+        variant_name: Option<&str>
+    ) -> Option<usize> {
+        match variant_name.and_then(|it| self.variant_index_by_name(it)) {
+            Some(variant) => primitive.material_for_variant(variant),
+            None => primitive.material()
+        }
+    }
+
+    pub fn animations(&self) -> &Vec<animation::AnimationInfo> {
+        &self.animations
+    }
+
+    pub fn animations_mut(&mut self) -> &mut Vec<animation::AnimationInfo> {
+        &mut self.animations
+    }
+
+    /// Samples the animation at the time into the animation state of one instance.
+    /// Returns false if there is no such animation.
+    pub fn sample_animation(&self, state: &mut AnimationState, index: usize, time: GltfVecNum) -> bool {
+        match self.animations.get(index) {
+            Some(animation) => {
+                state.apply_animation(animation, time);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Returns the pose the animations left the nodes of the instance in, with the constraints applied.
+    pub fn evaluate_pose(&self, state: &AnimationState) -> NodePose {
+        let mut pose = state.pose(self);
+        self.constraints.apply(self, &mut pose, state.constraint_targets());
+        pose
+    }
+
+    pub fn constraints(&self) -> &ConstraintSet {
+        &self.constraints
+    }
+
+    pub fn constraints_mut(&mut self) -> &mut ConstraintSet {
+        &mut self.constraints
+    }
 }
 
 impl<'a> LoadedGltfWrapper<'a> {
+    /// Wraps the glTF, giving it its default sampler and material.
     pub fn new(gltf: LoadedGltf<'a>) -> Self {
-        Self {
-            gltf: Arc::new(Mutex::new(gltf))
+        let gltf = Arc::new(Mutex::new(gltf));
+        let default_sampler = LoadedGltfSampler::new_default(&gltf);
+        let default_material = LoadedGltfMaterial::new_default(&gltf);
+        {
+            let mut loaded_gltf = gltf.lock().unwrap();
+            loaded_gltf.default_sampler = Some(default_sampler);
+            loaded_gltf.default_material = Some(default_material);
         }
+        Self { gltf }
     }
 
     pub fn get(&self) -> &Arc<Mutex<LoadedGltf<'a>>> {
@@ -0,0 +1,141 @@
+extern crate gltf;
+extern crate nalgebra;
+
+use gltf::json::Value;
+use crate::util::gltf::GltfVecNum;
+use nalgebra::{Matrix4, Quaternion, UnitQuaternion, Vector3};
+use super::LoadedGltf;
+
+pub const MESH_GPU_INSTANCING_EXTENSION: &str = "EXT_mesh_gpu_instancing";
+
+#[derive(Clone, Copy)]
+pub struct TransformInfo {
+    translation: Vector3<GltfVecNum>,
+    rotation: UnitQuaternion<GltfVecNum>,
+    scale: Vector3<GltfVecNum>
+}
+
+/// `EXT_mesh_gpu_instancing` of a node: the accessors holding the per-instance
+/// TRS, each of them being None if not defined in glTF.
+pub struct InstancingInfo {
+    translation: Option<usize>,
+    rotation: Option<usize>,
+    scale: Option<usize>
+}
+
+impl TransformInfo {
+    pub fn new(
+        translation: Vector3<GltfVecNum>,
+        rotation: UnitQuaternion<GltfVecNum>,
+        scale: Vector3<GltfVecNum>
+    ) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(Vector3::zeros(), UnitQuaternion::identity(), Vector3::new(1.0, 1.0, 1.0))
+    }
+
+    /// Both the matrix and the TRS forms of the glTF node transform are accepted,
+    /// the matrix form being decomposed into TRS.
+    pub fn new_from_transform(transform: gltf::scene::Transform) -> Self {
+        let (t, r, s) = transform.decomposed();
+        let translation = Vector3::new(t[0], t[1], t[2]);
+        // glTF stores quaternions as (x, y, z, w).
+        let rotation = UnitQuaternion::from_quaternion(Quaternion::new(r[3], r[0], r[1], r[2]));
+        let scale = Vector3::new(s[0], s[1], s[2]);
+        Self::new(translation, rotation, scale)
+    }
+
+    pub fn translation(&self) -> Vector3<GltfVecNum> {
+        self.translation
+    }
+
+    pub fn set_translation(&mut self, translation: Vector3<GltfVecNum>) {
+        self.translation = translation
+    }
+
+    pub fn rotation(&self) -> UnitQuaternion<GltfVecNum> {
+        self.rotation
+    }
+
+    pub fn set_rotation(&mut self, rotation: UnitQuaternion<GltfVecNum>) {
+        self.rotation = rotation
+    }
+
+    pub fn scale(&self) -> Vector3<GltfVecNum> {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: Vector3<GltfVecNum>) {
+        self.scale = scale
+    }
+
+    /// Returns the local matrix of the transform, which is T * R * S.
+    pub fn matrix(&self) -> Matrix4<GltfVecNum> {
+        Matrix4::new_translation(&self.translation)
+            * self.rotation.to_homogeneous()
+            * Matrix4::new_nonuniform_scaling(&self.scale)
+    }
+}
+
+impl InstancingInfo {
+    pub fn new_from_json(json: &Value) -> Self {
+        let accessor_of = |key: &str| json.get("attributes")
+            .and_then(|it| it.get(key))
+            .and_then(Value::as_u64)
+            .map(|it| it as usize);
+        Self {
+            translation: accessor_of("TRANSLATION"),
+            rotation: accessor_of("ROTATION"),
+            scale: accessor_of("SCALE")
+        }
+    }
+
+    pub fn translation(&self) -> Option<usize> {
+        self.translation
+    }
+
+    pub fn rotation(&self) -> Option<usize> {
+        self.rotation
+    }
+
+    pub fn scale(&self) -> Option<usize> {
+        self.scale
+    }
+
+    /// Reads the transforms of the instances, which are relative to the node.
+    /// Missing attributes leave their part of the transforms at identity.
+    pub fn read_instances(&self, gltf: &LoadedGltf) -> Vec<TransformInfo> {
+        let read = |index: Option<usize>| index
+            .and_then(|it| gltf.accessors().get(it))
+            .map(|it| it.read_components(gltf));
+        let translations = read(self.translation);
+        let rotations = read(self.rotation);
+        let scales = read(self.scale);
+        let count = [(&translations, 3), (&rotations, 4), (&scales, 3)].iter()
+            .filter_map(|(it, multiplicity)| it.as_ref().map(|it| it.len() / multiplicity))
+            .min()
+            .unwrap_or(0);
+
+        (0..count).map(|i| {
+            let mut result = TransformInfo::identity();
+            if let Some(t) = &translations {
+                result.set_translation(Vector3::new(t[i * 3], t[i * 3 + 1], t[i * 3 + 2]));
+            }
+            if let Some(r) = &rotations {
+                // glTF stores quaternions as (x, y, z, w).
+                result.set_rotation(UnitQuaternion::from_quaternion(
+                    Quaternion::new(r[i * 4 + 3], r[i * 4], r[i * 4 + 1], r[i * 4 + 2])));
+            }
+            if let Some(s) = &scales {
+                result.set_scale(Vector3::new(s[i * 3], s[i * 3 + 1], s[i * 3 + 2]));
+            }
+            result
+        }).collect()
+    }
+}
@@ -0,0 +1,40 @@
+extern crate gltf;
+
+use crate::util::gltf::extras_to_string;
+
+/// One scene of the glTF: the root nodes it draws.
+pub struct SceneInfo {
+    index: usize,
+    /// None if not defined in glTF.
+    name: Option<String>,
+    root_nodes: Vec<usize>,
+    /// The `extras` of the scene as JSON text. None if not defined in glTF.
+    extras: Option<String>
+}
+
+impl SceneInfo {
+    pub fn new_from_scene(scene: &gltf::Scene) -> Self {
+        Self {
+            index: scene.index(),
+            name: scene.name().map(String::from),
+            root_nodes: scene.nodes().map(|it| it.index()).collect(),
+            extras: extras_to_string(scene.extras())
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn root_nodes(&self) -> &Vec<usize> {
+        &self.root_nodes
+    }
+
+    pub fn extras(&self) -> Option<&String> {
+        self.extras.as_ref()
+    }
+}
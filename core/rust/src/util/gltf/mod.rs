@@ -1,11 +1,31 @@
 extern crate gltf;
 extern crate nalgebra;
+extern crate thiserror;
 
 mod loaded;
+mod pose;
+mod constraint;
+mod draw;
+mod animation_state;
+mod bake;
+mod pack;
 
+use gltf::json::validation::{Error, Validate};
+use gltf::material::AlphaMode;
 use gltf::texture::{MagFilter, MinFilter, WrappingMode};
+use thiserror::Error;
 use crate::constants;
 
+pub use loaded::animation;
+pub use loaded::asset;
+pub use loaded::buffer_view;
+pub use loaded::draco;
+pub use loaded::light;
+pub use loaded::material;
+pub use loaded::material_extension;
+pub use loaded::mesh;
+pub use loaded::node;
+pub use loaded::scene;
 pub use loaded::LoadedGltfBuffer;
 pub use loaded::LoadedGltfBufferView;
 pub use loaded::LoadedGltfAccessor;
@@ -16,9 +36,120 @@ pub use loaded::LoadedGltfMaterial;
 pub use loaded::LoadedGltf;
 pub use loaded::LoadedGltfWrapper;
 pub use loaded::LoadedGltfMesh;
+pub use loaded::LoadedGltfNode;
+pub use pose::NodePose;
+pub use constraint::Constraint;
+pub use constraint::ConstraintSet;
+pub use constraint::ConstraintTargets;
+pub use constraint::IkTarget;
+pub use constraint::LookAtConstraint;
+pub use constraint::LookAtTarget;
+pub use constraint::TwoBoneIkConstraint;
+pub use draw::DrawItem;
+pub use draw::collect_draw_items;
+pub use animation_state::AnimationState;
+pub use bake::bake_albedo;
+pub use bake::decode_texture_image;
+pub use pack::ChannelLayout;
+pub use pack::pack_material_maps;
 
 pub type GltfVecNum = f32;
 
+#[derive(Error, Debug)]
+pub enum GltfUtilError {
+    #[error("The glTF requires extensions which are not supported: {}", .0.join(", "))]
+    UnsupportedRequiredExtensions(Vec<String>)
+}
+
+/// The extensions the loader is able to handle, so that a glTF may list them in `extensionsRequired`.
+///
+/// `EXT_texture_avif` is left out since AVIF images cannot be decoded (see
+/// `util::image::is_format_decodable`): it is only read when a fallback source is there.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "EXT_mesh_gpu_instancing",
+    "EXT_meshopt_compression",
+    "KHR_draco_mesh_compression",
+    "KHR_lights_punctual",
+    "KHR_mesh_quantization",
+    "KHR_texture_transform",
+    "KHR_texture_basisu",
+    "EXT_texture_webp",
+    "KHR_animation_pointer",
+    "KHR_materials_unlit",
+    "KHR_materials_variants",
+    "KHR_materials_emissive_strength",
+    "KHR_materials_clearcoat",
+    "KHR_materials_sheen",
+    "KHR_materials_transmission",
+    "KHR_materials_ior",
+    "KHR_materials_specular",
+    "KHR_materials_volume",
+    "KHR_materials_iridescence",
+    "KHR_materials_anisotropy"
+];
+
+pub fn is_extension_supported(name: &str) -> bool {
+    SUPPORTED_EXTENSIONS.contains(&name)
+}
+
+/// Fails, naming them, if the glTF requires extensions the loader cannot handle,
+/// since the glTF could not be rendered correctly without them.
+pub fn check_required_extensions(gltf: &gltf::Gltf) -> Result<(), GltfUtilError> {
+    let unsupported: Vec<String> = gltf.extensions_required()
+        .filter(|it| !is_extension_supported(it))
+        .map(String::from)
+        .collect();
+    if unsupported.is_empty() {
+        Ok(())
+    } else {
+        Err(GltfUtilError::UnsupportedRequiredExtensions(unsupported))
+    }
+}
+
+/// Returns the `extras` of a glTF object as JSON text, None if it has none.
+pub fn extras_to_string(extras: &gltf::json::Extras) -> Option<String> {
+    extras.as_ref().map(|it| String::from(it.get()))
+}
+
+/// Parses a glTF or GLB without validating it, like `gltf::Gltf::from_slice_without_validation`,
+/// except that the `KHR_animation_pointer` channels are moved where the gltf crate accepts them
+/// (see `animation::move_pointer_channels`).
+pub fn parse_gltf(data: &[u8]) -> Result<gltf::Gltf, gltf::Error> {
+    let (mut json, blob): (gltf::json::Value, Option<Vec<u8>>) = if data.starts_with(b"glTF") {
+        let mut glb = gltf::Glb::from_slice(data)?;
+        (gltf::json::deserialize::from_slice(&glb.json)?, glb.bin.take().map(|it| it.into_owned()))
+    } else {
+        (gltf::json::deserialize::from_slice(data)?, None)
+    };
+    animation::move_pointer_channels(&mut json);
+    let root: gltf::json::Root = gltf::json::deserialize::from_value(json)?;
+    Ok(gltf::Gltf {
+        document: gltf::Document::from_json_without_validation(root),
+        blob
+    })
+}
+
+/// Validates the glTF the way `gltf::Gltf::from_slice` does, except that the required
+/// extensions the gltf crate does not know about but this loader supports are accepted.
+pub fn validate_gltf(gltf: &gltf::Gltf) -> Result<(), gltf::Error> {
+    let root = gltf.document.as_json();
+    let mut errors = Vec::new();
+    root.validate(root, gltf::json::Path::new, &mut |path, error| {
+        let path = path();
+        let supported_required_extension = matches!(error, Error::Unsupported)
+            && path.as_str().starts_with("extensionsRequired")
+            && SUPPORTED_EXTENSIONS.iter().any(|it| path.as_str().ends_with(&format!("\"{}\"", it)));
+        if !supported_required_extension {
+            errors.push((path, error));
+        }
+    });
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(gltf::Error::Validation(errors))
+    }
+}
+
 pub fn mag_filter_to_gl_value(filter: MagFilter) -> u32 {
     match filter {
         MagFilter::Nearest => constants::opengl::GL_NEAREST,
@@ -73,3 +204,36 @@ pub fn wrapping_mode_from_gl_value(value: u32) -> Option<WrappingMode> {
         _ => None
     }
 }
+
+/// The ids match the ones of the Kotlin `GltfAlphaMode` enum.
+pub fn alpha_mode_to_id(mode: AlphaMode) -> i32 {
+    match mode {
+        AlphaMode::Opaque => 0,
+        AlphaMode::Mask => 1,
+        AlphaMode::Blend => 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn required_extensions_check(extensions: &str) -> Result<(), GltfUtilError> {
+        let json = format!(
+            r#"{{ "asset": {{ "version": "2.0" }}, "extensionsUsed": {0}, "extensionsRequired": {0} }}"#,
+            extensions);
+        check_required_extensions(&parse_gltf(json.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn supported_required_extensions_pass() {
+        assert!(required_extensions_check(r#"["KHR_texture_basisu", "EXT_texture_webp"]"#).is_ok());
+    }
+
+    #[test]
+    fn undecodable_required_extensions_are_named() {
+        let err = required_extensions_check(r#"["EXT_texture_webp", "EXT_texture_avif", "EXT_unknown"]"#)
+            .err().unwrap();
+        assert_eq!(err.to_string(), "The glTF requires extensions which are not supported: EXT_texture_avif, EXT_unknown");
+    }
+}
@@ -0,0 +1,164 @@
+extern crate image;
+
+use anyhow::Result;
+use image::imageops::FilterType;
+use image::{Rgba, RgbaImage};
+use crate::util::image::srgb_to_linear;
+use super::{AnimationState, GltfVecNum, LoadedGltf, LoadedGltfMaterial};
+use super::bake::decode_texture_image;
+use super::material_extension::{Ior, Specular};
+
+/// The IOR glTF assumes for materials without `KHR_materials_ior`.
+const DEFAULT_IOR: GltfVecNum = 1.5;
+/// The highest LabPBR F0 value standing for a dielectric, the ones above being metals.
+const LABPBR_MAX_DIELECTRIC_F0: u8 = 229;
+/// The LabPBR F0 value of a metal whose reflectance is its albedo.
+const LABPBR_ALBEDO_METAL_F0: u8 = 255;
+
+/// The channel layouts the maps of a material can be packed into.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChannelLayout {
+    /// Occlusion in red, roughness in green and metalness in blue, as glTF reads them
+    /// when one texture is used as both the occlusion and the metallic-roughness texture.
+    GltfOrm,
+    /// The LabPBR `_s` texture: perceptual smoothness in red, F0 in green,
+    /// porosity/subsurface scattering in blue (left at 0) and emission in alpha.
+    LabPbrSpecular,
+    /// The LabPBR `_n` texture: the normal X and Y in red and green, occlusion in blue
+    /// and height in alpha (left flat, glTF having no height map).
+    LabPbrNormal
+}
+
+impl ChannelLayout {
+    /// The ids match the ones of the Kotlin `GltfChannelLayout` enum.
+    pub fn from_id(id: i32) -> Option<Self> {
+        match id {
+            0 => Some(Self::GltfOrm),
+            1 => Some(Self::LabPbrSpecular),
+            2 => Some(Self::LabPbrNormal),
+            _ => None
+        }
+    }
+}
+
+/// The decoded textures of the material a layout is packed from, scaled to the same size.
+struct MaterialMaps {
+    metallic_roughness: Option<RgbaImage>,
+    occlusion: Option<RgbaImage>,
+    normal: Option<RgbaImage>,
+    emissive: Option<RgbaImage>,
+    width: u32,
+    height: u32
+}
+
+impl MaterialMaps {
+    fn new(gltf: &LoadedGltf, material: &LoadedGltfMaterial, layout: ChannelLayout) -> Result<Self> {
+        let decode = |texture_index: Option<usize>| -> Result<Option<RgbaImage>> {
+            texture_index.map(|it| decode_texture_image(gltf, it)).transpose()
+        };
+        // Only the textures the layout reads are decoded.
+        let (metallic_roughness, occlusion, normal, emissive) = match layout {
+            ChannelLayout::GltfOrm => (true, true, false, false),
+            ChannelLayout::LabPbrSpecular => (true, false, false, true),
+            ChannelLayout::LabPbrNormal => (false, true, true, false)
+        };
+        let mut maps = Self {
+            metallic_roughness: decode(material.pbr_metallic_roughness().metallic_roughness_texture()
+                .as_ref().filter(|_| metallic_roughness).map(|it| it.index()))?,
+            occlusion: decode(material.occlusion_texture().as_ref().filter(|_| occlusion).map(|it| it.index()))?,
+            normal: decode(material.normal_texture().as_ref().filter(|_| normal).map(|it| it.index()))?,
+            emissive: decode(material.emissive_texture().as_ref().filter(|_| emissive).map(|it| it.index()))?,
+            width: 1,
+            height: 1
+        };
+
+        // The maps are packed at the size of the largest of them.
+        for image in [&maps.metallic_roughness, &maps.occlusion, &maps.normal, &maps.emissive]
+            .into_iter().flatten() {
+            maps.width = maps.width.max(image.width());
+            maps.height = maps.height.max(image.height());
+        }
+        let (width, height) = (maps.width, maps.height);
+        for image in [&mut maps.metallic_roughness, &mut maps.occlusion, &mut maps.normal, &mut maps.emissive]
+            .into_iter().flatten() {
+            if image.dimensions() != (width, height) {
+                *image = image::imageops::resize(image, width, height, FilterType::Triangle);
+            }
+        }
+        Ok(maps)
+    }
+}
+
+/// Returns the channel of the pixel of the map in [0, 1], or the default if there is no map.
+fn channel(map: &Option<RgbaImage>, x: u32, y: u32, index: usize, default: GltfVecNum) -> GltfVecNum {
+    map.as_ref().map(|it| it.get_pixel(x, y)[index] as GltfVecNum / 255.0).unwrap_or(default)
+}
+
+fn to_u8(value: GltfVecNum) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Packs the maps of the material into the channel layout, at the size of the largest of
+/// its textures (1x1 without any).
+///
+/// The factors, the occlusion strength and the normal scale of the material (as animated
+/// in the state, if any) are baked into the values, so that the packed texture stands on its own.
+pub fn pack_material_maps(
+    gltf: &LoadedGltf,
+    material: &LoadedGltfMaterial,
+    layout: ChannelLayout,
+    state: Option<&AnimationState>
+) -> Result<RgbaImage> {
+    let maps = MaterialMaps::new(gltf, material, layout)?;
+    let factors = material.factors(state);
+    let occlusion = |x: u32, y: u32| 1.0 + factors.occlusion_strength * (channel(&maps.occlusion, x, y, 0, 1.0) - 1.0);
+    // Roughness is in the green channel and metalness in the blue one.
+    let roughness = |x: u32, y: u32| channel(&maps.metallic_roughness, x, y, 1, 1.0) * factors.roughness_factor;
+    let metallic = |x: u32, y: u32| channel(&maps.metallic_roughness, x, y, 2, 1.0) * factors.metallic_factor;
+
+    match layout {
+        ChannelLayout::GltfOrm => Ok(RgbaImage::from_fn(maps.width, maps.height, |x, y| {
+            Rgba([to_u8(occlusion(x, y)), to_u8(roughness(x, y)), to_u8(metallic(x, y)), 255])
+        })),
+        ChannelLayout::LabPbrSpecular => {
+            let ior = material.extension::<Ior>().map(|it| it.ior).unwrap_or(DEFAULT_IOR);
+            let specular_factor = material.extension::<Specular>().map(|it| it.specular_factor).unwrap_or(1.0);
+            let dielectric_f0 = ((ior - 1.0) / (ior + 1.0)).powi(2) * specular_factor;
+            let dielectric_f0 = to_u8(dielectric_f0).min(LABPBR_MAX_DIELECTRIC_F0);
+            let emissive_factor = factors.emission();
+            Ok(RgbaImage::from_fn(maps.width, maps.height, |x, y| {
+                let f0 = if metallic(x, y) >= 0.5 { LABPBR_ALBEDO_METAL_F0 } else { dielectric_f0 };
+                let emission = (0..3)
+                    .map(|i| srgb_to_linear(channel(&maps.emissive, x, y, i, 1.0)) * emissive_factor[i])
+                    .fold(0.0, GltfVecNum::max)
+                    .clamp(0.0, 1.0);
+                // LabPBR reads emission from 0 to 254, 255 meaning none.
+                let emission = match (emission * 254.0).round() as u8 {
+                    0 => 255,
+                    it => it
+                };
+                Rgba([to_u8(1.0 - roughness(x, y)), f0, 0, emission])
+            }))
+        }
+        ChannelLayout::LabPbrNormal => {
+            let scale = factors.normal_scale;
+            Ok(RgbaImage::from_fn(maps.width, maps.height, |x, y| {
+                // Both glTF and LabPBR use OpenGL normal maps (green pointing up), so only the
+                // scale needs applying, keeping the scaled X and Y within the unit circle.
+                let mut normal_x = (channel(&maps.normal, x, y, 0, 0.5) * 2.0 - 1.0) * scale;
+                let mut normal_y = (channel(&maps.normal, x, y, 1, 0.5) * 2.0 - 1.0) * scale;
+                let length = (normal_x * normal_x + normal_y * normal_y).sqrt();
+                if length > 1.0 {
+                    normal_x /= length;
+                    normal_y /= length;
+                }
+                Rgba([
+                    to_u8(normal_x * 0.5 + 0.5),
+                    to_u8(normal_y * 0.5 + 0.5),
+                    to_u8(occlusion(x, y)),
+                    255
+                ])
+            }))
+        }
+    }
+}
@@ -0,0 +1,95 @@
+extern crate nalgebra;
+
+use nalgebra::{Matrix4, UnitQuaternion, Vector3};
+use super::{GltfVecNum, LoadedGltf};
+use super::loaded::node::TransformInfo;
+
+/// The local transforms of every node of a glTF, indexed by node index.
+///
+/// A pose starts as the rest pose of the glTF, gets overwritten by animation
+/// sampling and is then post-processed by the constraints.
+#[derive(Clone)]
+pub struct NodePose {
+    transforms: Vec<TransformInfo>
+}
+
+impl NodePose {
+    pub fn new(transforms: Vec<TransformInfo>) -> Self {
+        Self { transforms }
+    }
+
+    pub fn new_from_gltf(gltf: &LoadedGltf) -> Self {
+        Self::new(gltf.nodes().iter().map(|it| it.transform()).collect())
+    }
+
+    pub fn transforms(&self) -> &Vec<TransformInfo> {
+        &self.transforms
+    }
+
+    pub fn transforms_mut(&mut self) -> &mut Vec<TransformInfo> {
+        &mut self.transforms
+    }
+
+    pub fn transform(&self, index: usize) -> TransformInfo {
+        self.transforms[index]
+    }
+
+    pub fn set_transform(&mut self, index: usize, transform: TransformInfo) {
+        self.transforms[index] = transform
+    }
+
+    pub fn world_matrix(&self, gltf: &LoadedGltf, index: usize) -> Matrix4<GltfVecNum> {
+        let mut result = self.transforms[index].matrix();
+        let mut parent = gltf.nodes()[index].parent_index();
+        while let Some(parent_index) = parent {
+            result = self.transforms[parent_index].matrix() * result;
+            parent = gltf.nodes()[parent_index].parent_index();
+        }
+        result
+    }
+
+    pub fn world_matrices(&self, gltf: &LoadedGltf) -> Vec<Matrix4<GltfVecNum>> {
+        (0..self.transforms.len()).map(|it| self.world_matrix(gltf, it)).collect()
+    }
+
+    pub fn world_position(&self, gltf: &LoadedGltf, index: usize) -> Vector3<GltfVecNum> {
+        let matrix = self.world_matrix(gltf, index);
+        Vector3::new(matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)])
+    }
+
+    /// Returns the direction the -Z axis of the node points at in world space,
+    /// which is where lights and cameras face.
+    pub fn world_forward(&self, gltf: &LoadedGltf, index: usize) -> Vector3<GltfVecNum> {
+        let matrix = self.world_matrix(gltf, index);
+        let forward = -Vector3::new(matrix[(0, 2)], matrix[(1, 2)], matrix[(2, 2)]);
+        forward.try_normalize(GltfVecNum::EPSILON).unwrap_or(-Vector3::z())
+    }
+
+    /// Returns the world rotation of the node, ignoring the scale of its ancestors.
+    pub fn world_rotation(&self, gltf: &LoadedGltf, index: usize) -> UnitQuaternion<GltfVecNum> {
+        let mut result = self.transforms[index].rotation();
+        let mut parent = gltf.nodes()[index].parent_index();
+        while let Some(parent_index) = parent {
+            result = self.transforms[parent_index].rotation() * result;
+            parent = gltf.nodes()[parent_index].parent_index();
+        }
+        result
+    }
+
+    /// Applies a rotation given in world space to the node, by converting it
+    /// into the local space of the node's parent.
+    pub fn rotate_in_world(
+        &mut self,
+        gltf: &LoadedGltf,
+        index: usize,
+        delta: UnitQuaternion<GltfVecNum>
+    ) {
+        let parent_rotation = match gltf.nodes()[index].parent_index() {
+            Some(parent_index) => self.world_rotation(gltf, parent_index),
+            None => UnitQuaternion::identity()
+        };
+        let transform = &mut self.transforms[index];
+        transform.set_rotation(
+            parent_rotation.inverse() * delta * parent_rotation * transform.rotation());
+    }
+}
@@ -1,34 +1,349 @@
 extern crate image;
 extern crate anyhow;
 extern crate thiserror;
+extern crate basisu;
+extern crate ktx2;
+extern crate ruzstd;
 
-use image::ImageFormat;
+use std::io::{Cursor, Read};
+use image::imageops::FilterType;
+use image::{ImageFormat, Rgba, Rgba32FImage, RgbaImage};
 use anyhow::Result;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ImageUtilError {
     #[error("The image format id {0} is wrong.")]
-    WrongImageFormatId(i32)
+    WrongImageFormatId(i32),
+    #[error("The KTX2 image is malformed: {0}")]
+    MalformedKtx2(String),
+    #[error("The KTX2 image format {0} is not supported.")]
+    UnsupportedKtx2Format(String),
+    #[error("The image channel {0} is wrong.")]
+    WrongChannel(i32),
+    #[error("The resize filter id {0} is wrong.")]
+    WrongResizeFilterId(i32),
+    #[error("The power of two rounding id {0} is wrong.")]
+    WrongPowerOfTwoRoundingId(i32),
+    #[error("The image size {0}x{1} is wrong.")]
+    WrongSize(i32, i32)
 }
 
-pub fn image_format_from_id(id: i32) -> Result<ImageFormat> {
+/// The formats an image can be decoded from: those of the image crate, plus KTX2.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NativeImageFormat {
+    Image(ImageFormat),
+    Ktx2
+}
+
+/// The 12-byte identifier every KTX2 file starts with.
+pub const KTX2_IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A
+];
+
+pub fn image_format_from_id(id: i32) -> Result<NativeImageFormat> {
     match id {
-        0 => Ok(ImageFormat::Png),
-        1 => Ok(ImageFormat::Jpeg),
-        2 => Ok(ImageFormat::Gif),
-        3 => Ok(ImageFormat::WebP),
-        4 => Ok(ImageFormat::Pnm),
-        5 => Ok(ImageFormat::Tiff),
-        6 => Ok(ImageFormat::Tga),
-        7 => Ok(ImageFormat::Dds),
-        8 => Ok(ImageFormat::Bmp),
-        9 => Ok(ImageFormat::Ico),
-        10 => Ok(ImageFormat::Hdr),
-        11 => Ok(ImageFormat::OpenExr),
-        12 => Ok(ImageFormat::Farbfeld),
-        13 => Ok(ImageFormat::Avif),
-        14 => Ok(ImageFormat::Qoi),
+        0 => Ok(NativeImageFormat::Image(ImageFormat::Png)),
+        1 => Ok(NativeImageFormat::Image(ImageFormat::Jpeg)),
+        2 => Ok(NativeImageFormat::Image(ImageFormat::Gif)),
+        3 => Ok(NativeImageFormat::Image(ImageFormat::WebP)),
+        4 => Ok(NativeImageFormat::Image(ImageFormat::Pnm)),
+        5 => Ok(NativeImageFormat::Image(ImageFormat::Tiff)),
+        6 => Ok(NativeImageFormat::Image(ImageFormat::Tga)),
+        7 => Ok(NativeImageFormat::Image(ImageFormat::Dds)),
+        8 => Ok(NativeImageFormat::Image(ImageFormat::Bmp)),
+        9 => Ok(NativeImageFormat::Image(ImageFormat::Ico)),
+        10 => Ok(NativeImageFormat::Image(ImageFormat::Hdr)),
+        11 => Ok(NativeImageFormat::Image(ImageFormat::OpenExr)),
+        12 => Ok(NativeImageFormat::Image(ImageFormat::Farbfeld)),
+        13 => Ok(NativeImageFormat::Image(ImageFormat::Avif)),
+        14 => Ok(NativeImageFormat::Image(ImageFormat::Qoi)),
+        15 => Ok(NativeImageFormat::Ktx2),
         _ => Err(ImageUtilError::WrongImageFormatId(id).into())
     }
 }
+
+/// Whether images of the format can be decoded natively.
+pub fn is_format_decodable(format: NativeImageFormat) -> bool {
+    match format {
+        // The image crate only decodes AVIF with its `avif-native` feature,
+        // which is not enabled.
+        NativeImageFormat::Image(ImageFormat::Avif) => false,
+        NativeImageFormat::Image(format) => format.reading_enabled(),
+        NativeImageFormat::Ktx2 => true
+    }
+}
+
+/// Converts an sRGB-encoded component in [0, 1] into linear space.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear component in [0, 1] into sRGB encoding.
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Copies one channel (0 to 3 for red to alpha) of the image into the colour
+/// channels of an opaque greyscale image.
+pub fn extract_channel(image: &RgbaImage, channel: i32) -> Result<RgbaImage> {
+    if !(0..4).contains(&channel) {
+        return Err(ImageUtilError::WrongChannel(channel).into());
+    }
+    Ok(RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+        let value = image.get_pixel(x, y)[channel as usize];
+        image::Rgba([value, value, value, 255])
+    }))
+}
+
+/// Converts a unit value into a byte component.
+pub fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Converts the image to floating point with premultiplied alpha, in linear space if
+/// `gamma_correct`, for it to be filtered without dark fringes or transparent colours bleeding.
+pub fn to_premultiplied(image: &RgbaImage, gamma_correct: bool) -> Rgba32FImage {
+    Rgba32FImage::from_fn(image.width(), image.height(), |x, y| {
+        let pixel = image.get_pixel(x, y);
+        let alpha = pixel[3] as f32 / 255.0;
+        let color = |i: usize| {
+            let value = pixel[i] as f32 / 255.0;
+            if gamma_correct { srgb_to_linear(value) * alpha } else { value * alpha }
+        };
+        Rgba([color(0), color(1), color(2), alpha])
+    })
+}
+
+/// The reverse of `to_premultiplied`.
+pub fn from_premultiplied(image: &Rgba32FImage, gamma_correct: bool) -> RgbaImage {
+    RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+        let pixel = image.get_pixel(x, y);
+        let alpha = pixel[3].clamp(0.0, 1.0);
+        let color = |i: usize| {
+            let value = if alpha > 0.0 { (pixel[i] / alpha).clamp(0.0, 1.0) } else { 0.0 };
+            if gamma_correct { to_u8(linear_to_srgb(value)) } else { to_u8(value) }
+        };
+        Rgba([color(0), color(1), color(2), to_u8(alpha)])
+    })
+}
+
+/// The filters an image can be resampled with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResizeFilter {
+    /// Keeps the texels sharp, for pixel art.
+    Nearest,
+    Triangle,
+    CatmullRom,
+    Lanczos
+}
+
+impl ResizeFilter {
+    /// The ids match the ones of the Kotlin `ResizeFilter` enum.
+    pub fn from_id(id: i32) -> Result<Self> {
+        match id {
+            0 => Ok(Self::Nearest),
+            1 => Ok(Self::Triangle),
+            2 => Ok(Self::CatmullRom),
+            3 => Ok(Self::Lanczos),
+            _ => Err(ImageUtilError::WrongResizeFilterId(id).into())
+        }
+    }
+}
+
+/// How the sides of an image are rounded to powers of two.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerOfTwoRounding {
+    Keep,
+    Next,
+    Previous
+}
+
+impl PowerOfTwoRounding {
+    /// The ids match the ones of the Kotlin `PowerOfTwoRounding` enum.
+    pub fn from_id(id: i32) -> Result<Self> {
+        match id {
+            0 => Ok(Self::Keep),
+            1 => Ok(Self::Next),
+            2 => Ok(Self::Previous),
+            _ => Err(ImageUtilError::WrongPowerOfTwoRoundingId(id).into())
+        }
+    }
+}
+
+/// Returns the size an image of the size is normalised to: scaled down, keeping its aspect,
+/// so that no side exceeds `max_size` (if any), then with each side rounded to a power of two.
+/// A side rounded up past `max_size` is rounded down instead.
+pub fn normalized_size(
+    width: u32,
+    height: u32,
+    rounding: PowerOfTwoRounding,
+    max_size: Option<u32>
+) -> (u32, u32) {
+    let (mut width, mut height) = (width.max(1), height.max(1));
+    if let Some(max_size) = max_size.map(|it| it.max(1)) {
+        if width > max_size || height > max_size {
+            let scale = max_size as f64 / width.max(height) as f64;
+            width = ((width as f64 * scale).round() as u32).clamp(1, max_size);
+            height = ((height as f64 * scale).round() as u32).clamp(1, max_size);
+        }
+    }
+    let previous = |it: u32| 1 << (31 - it.leading_zeros());
+    let round = |it: u32| match rounding {
+        PowerOfTwoRounding::Keep => it,
+        PowerOfTwoRounding::Next => match max_size {
+            Some(max_size) if it.next_power_of_two() > max_size => previous(it),
+            _ => it.next_power_of_two()
+        },
+        PowerOfTwoRounding::Previous => previous(it)
+    };
+    (round(width), round(height))
+}
+
+/// Resamples the image to the size with the filter, in linear space if `gamma_correct`
+/// (except with the nearest filter, which copies texels as they are).
+pub fn resize(image: &RgbaImage, width: u32, height: u32, filter: ResizeFilter, gamma_correct: bool) -> RgbaImage {
+    if image.dimensions() == (width, height) {
+        return image.clone();
+    }
+    let filter_type = match filter {
+        ResizeFilter::Nearest => return image::imageops::resize(image, width, height, FilterType::Nearest),
+        ResizeFilter::Triangle => FilterType::Triangle,
+        ResizeFilter::CatmullRom => FilterType::CatmullRom,
+        ResizeFilter::Lanczos => FilterType::Lanczos3
+    };
+    let premultiplied = to_premultiplied(image, gamma_correct);
+    from_premultiplied(&image::imageops::resize(&premultiplied, width, height, filter_type), gamma_correct)
+}
+
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>> {
+    let mut result = Vec::new();
+    image.write_to(&mut Cursor::new(&mut result), ImageFormat::Png)?;
+    Ok(result)
+}
+
+pub fn is_ktx2(data: &[u8]) -> bool {
+    data.starts_with(&KTX2_IDENTIFIER)
+}
+
+/// Decodes an image into its mip levels, the full-sized image coming first.
+/// Only KTX2 images may have more than one level.
+///
+/// The format is guessed from the data if not given.
+pub fn decode_image_levels(data: &[u8], format: Option<NativeImageFormat>) -> Result<Vec<RgbaImage>> {
+    let format = match format {
+        Some(format) => format,
+        None if is_ktx2(data) => NativeImageFormat::Ktx2,
+        None => {
+            let image = image::ImageReader::new(Cursor::new(data))
+                .with_guessed_format()?.decode()?;
+            return Ok(vec![image.to_rgba8()]);
+        }
+    };
+    match format {
+        NativeImageFormat::Image(format) => {
+            let mut image_reader = image::ImageReader::new(Cursor::new(data));
+            image_reader.set_format(format);
+            Ok(vec![image_reader.decode()?.to_rgba8()])
+        }
+        NativeImageFormat::Ktx2 => decode_ktx2_levels(data)
+    }
+}
+
+/// Decodes every mip level of a KTX2 image to RGBA8.
+///
+/// Basis Universal payloads (ETC1S and UASTC, as used by `KHR_texture_basisu`) are
+/// transcoded, while plain R8G8B8A8 images are copied out, after the zstd
+/// supercompression of their levels is undone.
+pub fn decode_ktx2_levels(data: &[u8]) -> Result<Vec<RgbaImage>> {
+    let reader = ktx2::Reader::new(data)
+        .map_err(|err| ImageUtilError::MalformedKtx2(err.to_string()))?;
+    let header = reader.header();
+    match header.format {
+        // A Basis Universal payload has no Vulkan format.
+        None => decode_basisu_levels(data),
+        Some(ktx2::Format::R8G8B8A8_UNORM) | Some(ktx2::Format::R8G8B8A8_SRGB) => {
+            reader.levels().enumerate().map(|(level, it)| {
+                let width = (header.pixel_width >> level).max(1);
+                let height = (header.pixel_height >> level).max(1);
+                let pixels = match header.supercompression_scheme {
+                    None => it.data.to_vec(),
+                    Some(ktx2::SupercompressionScheme::Zstandard) => {
+                        let mut source = it.data;
+                        let mut decoder = ruzstd::StreamingDecoder::new(&mut source)
+                            .map_err(|err| ImageUtilError::MalformedKtx2(err.to_string()))?;
+                        let mut result = Vec::with_capacity(it.uncompressed_byte_length as usize);
+                        decoder.read_to_end(&mut result)?;
+                        result
+                    }
+                    Some(scheme) => return Err(ImageUtilError::UnsupportedKtx2Format(
+                        format!("supercompression {:?}", scheme)).into())
+                };
+                // Only the first layer/face of the level is kept.
+                let length = (width * height * 4) as usize;
+                if pixels.len() < length {
+                    return Err(ImageUtilError::MalformedKtx2(
+                        format!("level {} is shorter than {}x{} pixels", level, width, height)).into());
+                }
+                Ok(RgbaImage::from_raw(width, height, pixels[..length].to_vec()).unwrap())
+            }).collect()
+        }
+        Some(format) => Err(ImageUtilError::UnsupportedKtx2Format(format!("{:?}", format)).into())
+    }
+}
+
+fn decode_basisu_levels(data: &[u8]) -> Result<Vec<RgbaImage>> {
+    let transcoder = basisu::Transcoder::new(data)
+        .map_err(|err| ImageUtilError::MalformedKtx2(format!("{:?}", err)))?;
+    (0..transcoder.level_count()).map(|level| {
+        let info = transcoder.image_level_info(level)
+            .map_err(|err| ImageUtilError::MalformedKtx2(format!("{:?}", err)))?;
+        let pixels = transcoder.transcode(level, basisu::TargetFormat::Rgba32, basisu::DecodeFlags::NONE)
+            .map_err(|err| ImageUtilError::MalformedKtx2(format!("{:?}", err)))?;
+        RgbaImage::from_raw(info.width, info.height, pixels).ok_or_else(|| ImageUtilError::MalformedKtx2(
+            format!("level {} does not hold {}x{} pixels", level, info.width, info.height)).into())
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized_size_rounds_each_side() {
+        assert_eq!(normalized_size(300, 200, PowerOfTwoRounding::Keep, None), (300, 200));
+        assert_eq!(normalized_size(300, 200, PowerOfTwoRounding::Next, None), (512, 256));
+        assert_eq!(normalized_size(300, 200, PowerOfTwoRounding::Previous, None), (256, 128));
+        assert_eq!(normalized_size(256, 64, PowerOfTwoRounding::Next, None), (256, 64));
+        assert_eq!(normalized_size(256, 64, PowerOfTwoRounding::Previous, None), (256, 64));
+    }
+
+    #[test]
+    fn normalized_size_scales_down_to_the_max_size_keeping_the_aspect() {
+        assert_eq!(normalized_size(1000, 500, PowerOfTwoRounding::Keep, Some(256)), (256, 128));
+        assert_eq!(normalized_size(300, 900, PowerOfTwoRounding::Keep, Some(300)), (100, 300));
+        assert_eq!(normalized_size(200, 100, PowerOfTwoRounding::Keep, Some(256)), (200, 100));
+    }
+
+    #[test]
+    fn normalized_size_does_not_round_past_the_max_size() {
+        assert_eq!(normalized_size(300, 100, PowerOfTwoRounding::Next, Some(256)), (256, 128));
+        assert_eq!(normalized_size(140, 100, PowerOfTwoRounding::Next, Some(150)), (128, 128));
+        assert_eq!(normalized_size(1000, 10, PowerOfTwoRounding::Previous, Some(512)), (512, 4));
+    }
+
+    #[test]
+    fn normalized_size_keeps_sides_at_least_one() {
+        assert_eq!(normalized_size(0, 0, PowerOfTwoRounding::Next, None), (1, 1));
+        assert_eq!(normalized_size(4096, 1, PowerOfTwoRounding::Keep, Some(16)), (16, 1));
+        assert_eq!(normalized_size(64, 64, PowerOfTwoRounding::Next, Some(0)), (1, 1));
+    }
+}
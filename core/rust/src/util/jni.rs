@@ -1,7 +1,8 @@
 extern crate jni;
 
 use jni::JNIEnv;
-use jni::sys::{jboolean, jbyteArray};
+use jni::objects::JObject;
+use jni::sys::{jboolean, jbyteArray, jfloat, jfloatArray, jint, jintArray, jobjectArray, jsize};
 use anyhow::{Context, Result};
 
 pub fn bool_to_jboolean(val: bool) -> jboolean {
@@ -9,7 +10,7 @@ pub fn bool_to_jboolean(val: bool) -> jboolean {
 }
 
 pub fn jboolean_to_bool(val: jboolean) -> bool {
-    if val == 0 { false } else { true }
+    val != 0
 }
 
 pub fn throw_runtime_exception(env: &mut JNIEnv, message: &String) -> Result<()> {
@@ -22,7 +23,7 @@ pub fn throw_runtime_exception(env: &mut JNIEnv, message: &String) -> Result<()>
 }
 
 pub fn clear_exception_if_occurred(env: &mut JNIEnv) {
-    if let Ok(_) = env.exception_occurred() {
+    if env.exception_occurred().is_ok() {
         env.exception_clear().unwrap();
     }
 }
@@ -30,3 +31,24 @@ pub fn clear_exception_if_occurred(env: &mut JNIEnv) {
 pub fn new_empty_byte_array(env: &mut JNIEnv) -> jbyteArray {
     env.new_byte_array(0).unwrap().as_raw()
 }
+
+pub fn new_float_array(env: &mut JNIEnv, data: &[jfloat]) -> Result<jfloatArray> {
+    let jarr = env.new_float_array(data.len() as jsize)?;
+    env.set_float_array_region(&jarr, 0, data)?;
+    Ok(jarr.as_raw())
+}
+
+pub fn new_int_array(env: &mut JNIEnv, data: &[jint]) -> Result<jintArray> {
+    let jarr = env.new_int_array(data.len() as jsize)?;
+    env.set_int_array_region(&jarr, 0, data)?;
+    Ok(jarr.as_raw())
+}
+
+pub fn new_string_array(env: &mut JNIEnv, data: &[String]) -> Result<jobjectArray> {
+    let jarr = env.new_object_array(data.len() as jsize, "java/lang/String", JObject::null())?;
+    for (i, item) in data.iter().enumerate() {
+        let item_jstr = env.new_string(item)?;
+        env.set_object_array_element(&jarr, i as jsize, item_jstr)?;
+    }
+    Ok(jarr.as_raw())
+}
@@ -0,0 +1,195 @@
+extern crate image;
+
+use image::imageops::FilterType;
+use image::{Rgba, Rgba32FImage, RgbaImage};
+use thiserror::Error;
+use anyhow::Result;
+use crate::util::image::{from_premultiplied, to_premultiplied, to_u8};
+
+/// How many steps the search for the alpha scale preserving the coverage takes.
+const ALPHA_COVERAGE_SEARCH_STEPS: usize = 16;
+/// The largest alpha scale the coverage preservation may apply.
+const MAX_ALPHA_SCALE: f32 = 4.0;
+
+#[derive(Error, Debug)]
+pub enum MipmapError {
+    #[error("The mipmap filter id {0} is wrong.")]
+    WrongMipmapFilterId(i32)
+}
+
+/// The filters a mip level can be downsampled from the previous one with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MipmapFilter {
+    /// Averages each 2x2 block, which is the cheapest and what OpenGL does.
+    Box,
+    Triangle,
+    /// Keeps more detail than the others, at the price of some ringing.
+    Lanczos
+}
+
+impl MipmapFilter {
+    /// The ids match the ones of the Kotlin `MipmapFilter` enum.
+    pub fn from_id(id: i32) -> Result<Self> {
+        match id {
+            0 => Ok(Self::Box),
+            1 => Ok(Self::Triangle),
+            2 => Ok(Self::Lanczos),
+            _ => Err(MipmapError::WrongMipmapFilterId(id).into())
+        }
+    }
+}
+
+/// How the mip levels of an image are built.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MipmapOptions {
+    pub filter: MipmapFilter,
+    /// Whether the colours are sRGB-encoded, and so have to be averaged in linear space
+    /// not to darken the smaller levels.
+    pub gamma_correct: bool,
+    /// The alpha cutoff of the cutout texture, if any, with which the alpha of the smaller
+    /// levels is scaled so that as many texels pass the alpha test as in the full-sized one.
+    pub alpha_cutoff: Option<f32>
+}
+
+/// Builds the mip levels below the full-sized image, halving the size down to 1x1.
+///
+/// The levels are downsampled from one another in floating point with premultiplied
+/// alpha, so that the colours of transparent texels do not bleed into the others.
+pub fn generate_mip_levels(image: &RgbaImage, options: MipmapOptions) -> Vec<RgbaImage> {
+    let target_coverage = options.alpha_cutoff.map(|it| alpha_coverage(image, it, 1.0));
+    let mut level = to_premultiplied(image, options.gamma_correct);
+    let mut result = Vec::new();
+    while level.width() > 1 || level.height() > 1 {
+        let width = (level.width() / 2).max(1);
+        let height = (level.height() / 2).max(1);
+        level = match options.filter {
+            MipmapFilter::Box => downsample_box(&level, width, height),
+            MipmapFilter::Triangle => image::imageops::resize(&level, width, height, FilterType::Triangle),
+            MipmapFilter::Lanczos => image::imageops::resize(&level, width, height, FilterType::Lanczos3)
+        };
+        let mut output = from_premultiplied(&level, options.gamma_correct);
+        if let (Some(cutoff), Some(coverage)) = (options.alpha_cutoff, target_coverage) {
+            preserve_alpha_coverage(&mut output, cutoff, coverage);
+        }
+        result.push(output);
+    }
+    result
+}
+
+/// Averages the 2x2 blocks of the image, the blocks of an odd edge being cut short.
+fn downsample_box(image: &Rgba32FImage, width: u32, height: u32) -> Rgba32FImage {
+    Rgba32FImage::from_fn(width, height, |x, y| {
+        let xs = (x * 2)..(x * 2 + 2).min(image.width());
+        let ys = (y * 2)..(y * 2 + 2).min(image.height());
+        let count = (xs.len() * ys.len()) as f32;
+        let mut sum = [0.0; 4];
+        for sy in ys {
+            for sx in xs.clone() {
+                let pixel = image.get_pixel(sx, sy);
+                for (i, it) in sum.iter_mut().enumerate() {
+                    *it += pixel[i];
+                }
+            }
+        }
+        Rgba(sum.map(|it| it / count))
+    })
+}
+
+/// Returns the fraction of the texels which pass the alpha test once their alpha is scaled.
+fn alpha_coverage(image: &RgbaImage, cutoff: f32, scale: f32) -> f32 {
+    let covered = image.pixels()
+        .filter(|it| it[3] as f32 / 255.0 * scale >= cutoff)
+        .count();
+    covered as f32 / (image.width() * image.height()) as f32
+}
+
+/// Scales the alpha of the level so that its coverage is the closest to the target one.
+fn preserve_alpha_coverage(image: &mut RgbaImage, cutoff: f32, target_coverage: f32) {
+    let mut low = 0.0;
+    let mut high = MAX_ALPHA_SCALE;
+    let mut best = (1.0, (alpha_coverage(image, cutoff, 1.0) - target_coverage).abs());
+    for _ in 0..ALPHA_COVERAGE_SEARCH_STEPS {
+        let scale = (low + high) / 2.0;
+        let coverage = alpha_coverage(image, cutoff, scale);
+        if (coverage - target_coverage).abs() < best.1 {
+            best = (scale, (coverage - target_coverage).abs());
+        }
+        if coverage < target_coverage {
+            low = scale;
+        } else {
+            high = scale;
+        }
+    }
+    if best.0 != 1.0 {
+        for pixel in image.pixels_mut() {
+            pixel[3] = to_u8(pixel[3] as f32 / 255.0 * best.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(filter: MipmapFilter, gamma_correct: bool, alpha_cutoff: Option<f32>) -> MipmapOptions {
+        MipmapOptions { filter, gamma_correct, alpha_cutoff }
+    }
+
+    /// An image with a noisy alpha, whose coverage drops once averaged since the alpha
+    /// tends to its mean the smaller the level.
+    fn noisy_alpha(size: u32) -> RgbaImage {
+        RgbaImage::from_fn(size, size, |x, y| {
+            let mut hash = x.wrapping_mul(374_761_393).wrapping_add(y.wrapping_mul(668_265_263));
+            hash = (hash ^ (hash >> 13)).wrapping_mul(1_274_126_177);
+            Rgba([255, 255, 255, (hash ^ (hash >> 16)) as u8])
+        })
+    }
+
+    #[test]
+    fn levels_halve_down_to_one_by_one() {
+        let image = RgbaImage::new(64, 16);
+        let sizes: Vec<(u32, u32)> = generate_mip_levels(&image, options(MipmapFilter::Box, false, None))
+            .iter().map(|it| it.dimensions()).collect();
+        assert_eq!(sizes, vec![(32, 8), (16, 4), (8, 2), (4, 1), (2, 1), (1, 1)]);
+        assert!(generate_mip_levels(&RgbaImage::new(1, 1), options(MipmapFilter::Box, false, None)).is_empty());
+    }
+
+    #[test]
+    fn box_filter_averages_in_linear_space_if_gamma_correct() {
+        let image = RgbaImage::from_fn(2, 1, |x, _| if x == 0 { Rgba([0, 0, 0, 255]) } else { Rgba([255, 255, 255, 255]) });
+        let linear = generate_mip_levels(&image, options(MipmapFilter::Box, true, None));
+        let encoded = generate_mip_levels(&image, options(MipmapFilter::Box, false, None));
+        // The sRGB encoding of a linear 0.5.
+        assert!((187..=189).contains(&linear[0].get_pixel(0, 0)[0]));
+        assert!((127..=128).contains(&encoded[0].get_pixel(0, 0)[0]));
+    }
+
+    #[test]
+    fn transparent_colours_do_not_bleed() {
+        let image = RgbaImage::from_fn(2, 1, |x, _| if x == 0 { Rgba([255, 0, 0, 255]) } else { Rgba([0, 255, 0, 0]) });
+        let levels = generate_mip_levels(&image, options(MipmapFilter::Box, true, None));
+        let pixel = levels[0].get_pixel(0, 0);
+        assert_eq!((pixel[0], pixel[1], pixel[2]), (255, 0, 0));
+        assert!((127..=128).contains(&pixel[3]));
+    }
+
+    #[test]
+    fn alpha_cutoff_preserves_the_coverage() {
+        let cutoff = 0.7;
+        let image = noisy_alpha(64);
+        let target = alpha_coverage(&image, cutoff, 1.0);
+        let plain = generate_mip_levels(&image, options(MipmapFilter::Box, false, None));
+        let preserved = generate_mip_levels(&image, options(MipmapFilter::Box, false, Some(cutoff)));
+
+        // Down to 4x4, each level keeps the coverage within a few texels.
+        for (plain, preserved) in plain.iter().zip(&preserved).take_while(|it| it.0.width() >= 4) {
+            let texel = 1.0 / (preserved.width() * preserved.height()) as f32;
+            let coverage = alpha_coverage(preserved, cutoff, 1.0);
+            assert!((coverage - target).abs() <= (2.0 * texel).max(0.02),
+                "{}x{}: {} instead of {}", preserved.width(), preserved.height(), coverage, target);
+            assert!(alpha_coverage(plain, cutoff, 1.0) <= coverage);
+        }
+        // Without it, the coverage falls away.
+        assert!(alpha_coverage(&plain[2], cutoff, 1.0) < target / 2.0);
+    }
+}
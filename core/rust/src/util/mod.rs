@@ -1,6 +1,8 @@
 pub mod jni;
 pub mod error_message;
 pub mod image;
+pub mod mipmap;
+pub mod atlas;
 pub mod bits;
 pub mod gltf;
 
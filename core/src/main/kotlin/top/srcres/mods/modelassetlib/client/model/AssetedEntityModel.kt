@@ -9,6 +9,7 @@ import net.minecraft.world.entity.Entity
 import top.srcres.mods.modelassetlib.ModelAssetLib
 import top.srcres.mods.modelassetlib.client.renderer.texture.AssetedTexture
 import top.srcres.mods.modelassetlib.gltf.DefaultGltf
+import top.srcres.mods.modelassetlib.gltf.GltfAnimationState
 import top.srcres.mods.modelassetlib.image.ImageFormat
 import java.io.Closeable
 import java.io.InputStream
@@ -23,6 +24,11 @@ class AssetedEntityModel<T : Entity?>(
 ) : EntityModel<T>(), Closeable {
     private val gltf: DefaultGltf
 
+    /**
+     * The animation state of each entity drawn with the model, by entity id.
+     */
+    private val animationStates = HashMap<Int, GltfAnimationState>()
+
     constructor(input: InputStream)
             : this(input.use { it.readAllBytes() })
 
@@ -36,15 +42,41 @@ class AssetedEntityModel<T : Entity?>(
         gltf = DefaultGltf(gltfData, ::loadBufferFromURI, ::loadImageFromURI)
         gltf.init()
 
+        for (ext in gltf.getIgnoredExtensions()) {
+            ModelAssetLib.logger.warn("glTF extension $ext is not supported and will be ignored.")
+        }
+
+        for (ext in gltf.getUnhandledMaterialExtensions()) {
+            ModelAssetLib.logger.warn("Material extension $ext is not supported and will be ignored.")
+        }
+
         for (uri in gltf.imageURIList) {
             val data = gltf.getImageDataByURI(uri)
             val location = ResourceLocation(uri)
             val format = ImageFormat.fromExtension(getExtensionFromURI(uri))
-            ModelAssetLib.mcInstance.textureManager.register(location, AssetedTexture(location, data, format))
+            ModelAssetLib.mcInstance.textureManager.register(
+                location,
+                AssetedTexture(location, data, format, gltf.getImageSamplerParameters(uri), gltf.getImageUsage(uri))
+            )
         }
     }
 
+    /**
+     * Returns the animation state of the entity, created on first use.
+     */
+    fun getAnimationState(entity: Entity): GltfAnimationState =
+        animationStates.getOrPut(entity.id) { GltfAnimationState() }
+
+    /**
+     * Frees the animation state of the entity, once it is no longer drawn with the model.
+     */
+    fun releaseAnimationState(entity: Entity) {
+        animationStates.remove(entity.id)?.close()
+    }
+
     override fun close() {
+        animationStates.values.forEach { it.close() }
+        animationStates.clear()
         gltf.close()
     }
 
@@ -77,6 +109,8 @@ class AssetedEntityModel<T : Entity?>(
         pNetHeadYaw: Float,
         pHeadPitch: Float
     ) {
-        TODO("Not yet implemented")
+        if (pEntity != null) {
+            gltf.setLookAtAngles(getAnimationState(pEntity), pNetHeadYaw, pHeadPitch)
+        }
     }
 }
\ No newline at end of file
@@ -8,15 +8,21 @@ import net.minecraft.server.packs.resources.ResourceManager
 import org.lwjgl.opengl.GL11
 import org.lwjgl.system.MemoryUtil
 import top.srcres.mods.modelassetlib.ModelAssetLib
+import top.srcres.mods.modelassetlib.gltf.GltfImageUsage
+import top.srcres.mods.modelassetlib.gltf.GltfSamplerParameters
 import top.srcres.mods.modelassetlib.image.Image
 import top.srcres.mods.modelassetlib.image.ImageFormat
+import top.srcres.mods.modelassetlib.image.PowerOfTwoRounding
+import top.srcres.mods.modelassetlib.image.ResizeFilter
 import top.srcres.mods.modelassetlib.util.MemoryUtilWrapper
 import java.util.*
 
 class AssetedTexture(
     val location: ResourceLocation,
     rawData: ByteArray,
-    format: Optional<ImageFormat>
+    format: Optional<ImageFormat>,
+    private val samplerParameters: GltfSamplerParameters? = null,
+    private val usage: GltfImageUsage? = null
 ) : AbstractTexture() {
     // Using the NativeImage#read method will straightly lead to java.lang.OutOfMemoryError: Out of stack space.
     // Hence, we are forced to implement image source decoding algorithm on our own.
@@ -47,24 +53,46 @@ class AssetedTexture(
         // to keep away from unexpected behaviours.
         RenderSystem.assertOnRenderThreadOrInit()
 
-        TextureUtil.prepareImage(this.id, this.pixels.width, this.pixels.height)
+        // Older drivers cannot mipmap non-power-of-two textures, and some textures exceed what
+        // the GPU supports. Pixel art, sampled with the nearest filter, is kept sharp.
+        val width = this.pixels.width
+        val height = this.pixels.height
+        val maxSize = RenderSystem.maxSupportedTextureSize()
+        if (width.countOneBits() != 1 || height.countOneBits() != 1 || width > maxSize || height > maxSize) {
+            val filter = if (samplerParameters?.magFilter == GL11.GL_NEAREST) ResizeFilter.Nearest else ResizeFilter.Lanczos
+            this.pixels.normalizeSize(PowerOfTwoRounding.Next, maxSize, filter)
+        }
+
+        // Samplers may ask for mipmap min filters, which sample an incomplete texture
+        // unless every level down to 1x1 is uploaded. Data maps are averaged as they are,
+        // and cutout textures keep their coverage. Images of unknown use are taken as colours.
+        if (this.pixels.mipLevelCount == 1) {
+            this.pixels.generateMipmaps(
+                gammaCorrect = usage?.color ?: true,
+                alphaCutoff = usage?.alphaCutoff
+            )
+        }
+        TextureUtil.prepareImage(this.id, this.pixels.mipLevelCount - 1, this.pixels.width, this.pixels.height)
         upload()
     }
 
     private fun upload() {
         this.bind()
 
-        val rgbaData = pixels.rgbaData
-        val rgbaDataBuf = MemoryUtilWrapper.memWrapByteArray(rgbaData)
-        val rgbaDataBufAddr = MemoryUtil.memAddress(rgbaDataBuf)
-        val width = pixels.width
-        val height = pixels.height
-        GL11.glTexImage2D(GL11.GL_TEXTURE_2D, 0, GL11.GL_RGBA, width, height, 0, GL11.GL_RGBA, GL11.GL_UNSIGNED_BYTE, rgbaDataBufAddr)
+        for (level in 0 until pixels.mipLevelCount) {
+            val rgbaData = pixels.getMipLevelRgbaData(level)
+            val rgbaDataBuf = MemoryUtilWrapper.memWrapByteArray(rgbaData)
+            val rgbaDataBufAddr = MemoryUtil.memAddress(rgbaDataBuf)
+            val width = pixels.getMipLevelWidth(level)
+            val height = pixels.getMipLevelHeight(level)
+            GL11.glTexImage2D(GL11.GL_TEXTURE_2D, level, GL11.GL_RGBA, width, height, 0, GL11.GL_RGBA, GL11.GL_UNSIGNED_BYTE, rgbaDataBufAddr)
+        }
 
-        /*
-        TODO: Set up texture parameters by invoking OpenGL's glTexParameteri
-         with the data from glTF file's "sampler" section referred by the
-         "textures" section.
-         */
+        samplerParameters?.let {
+            GL11.glTexParameteri(GL11.GL_TEXTURE_2D, GL11.GL_TEXTURE_MAG_FILTER, it.magFilter)
+            GL11.glTexParameteri(GL11.GL_TEXTURE_2D, GL11.GL_TEXTURE_MIN_FILTER, it.minFilter)
+            GL11.glTexParameteri(GL11.GL_TEXTURE_2D, GL11.GL_TEXTURE_WRAP_S, it.wrapS)
+            GL11.glTexParameteri(GL11.GL_TEXTURE_2D, GL11.GL_TEXTURE_WRAP_T, it.wrapT)
+        }
     }
 }
\ No newline at end of file
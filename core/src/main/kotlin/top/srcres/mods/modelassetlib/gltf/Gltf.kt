@@ -1,5 +1,8 @@
 package top.srcres.mods.modelassetlib.gltf
 
+import top.srcres.mods.modelassetlib.image.Image
+import top.srcres.mods.modelassetlib.image.TextureAtlas
+
 abstract class Gltf(
     gltfData: ByteArray
 ) : AutoCloseable {
@@ -37,6 +40,414 @@ abstract class Gltf(
 
     external fun getImageDataByURI(uriStr: String): ByteArray
 
+    /**
+     * Adds a look-at constraint to the node named [nodeName], with the limits given in degrees.
+     * Returns the id of the constraint.
+     */
+    external fun addLookAtConstraint(nodeName: String, minYaw: Float, maxYaw: Float, minPitch: Float, maxPitch: Float): Int
+
+    /**
+     * Adds a two-bone IK constraint to the limb chain [rootName] -> [midName] -> [endName],
+     * each node having to be the parent of the next one. Returns the id of the constraint.
+     *
+     * The constraint is disabled in every [GltfAnimationState] until its target is set with [setIkTarget].
+     */
+    external fun addTwoBoneIkConstraint(rootName: String, midName: String, endName: String): Int
+
+    /**
+     * Sets the yaw and pitch, in degrees, of every look-at constraint for the instance of [state].
+     */
+    external fun setLookAtAngles(state: GltfAnimationState, yaw: Float, pitch: Float)
+
+    /**
+     * Sets the target and the pole target, in model space, of the two-bone IK constraint [constraintId]
+     * for the instance of [state].
+     */
+    external fun setIkTarget(state: GltfAnimationState, constraintId: Int, targetX: Float, targetY: Float, targetZ: Float, poleX: Float, poleY: Float, poleZ: Float)
+
+    /**
+     * Returns the world matrices of all nodes, 16 floats each in column-major order,
+     * in the pose the animations sampled into [state] left them in, with the constraints applied
+     * with the targets of [state].
+     */
+    external fun evaluatePose(state: GltfAnimationState): FloatArray
+
+    external fun getAssetGenerator(): String?
+
+    external fun getAssetVersion(): String
+
+    external fun getAssetCopyright(): String?
+
+    external fun getAssetMinVersion(): String?
+
+    external fun getExtensionsUsed(): Array<String>
+
+    external fun getExtensionsRequired(): Array<String>
+
+    /**
+     * Returns the extensions listed in `extensionsUsed` which the native loader does not support
+     * and ignores. Loading fails instead if such an extension is listed in `extensionsRequired`.
+     */
+    external fun getIgnoredExtensions(): Array<String>
+
+    /**
+     * Returns the root `extras` of the glTF as JSON text, or null if there are none.
+     */
+    external fun getRootExtras(): String?
+
+    private external fun getExtras0(kindId: Int, index: Int): String?
+
+    /**
+     * Returns the `extras` of the object of [kind] at [index] as JSON text, or null if it has none.
+     */
+    fun getExtras(kind: GltfObjectKind, index: Int) = getExtras0(kind.id, index)
+
+    /**
+     * Returns the `extras` of the primitive as JSON text, or null if it has none.
+     */
+    external fun getPrimitiveExtras(meshIndex: Int, primitiveIndex: Int): String?
+
+    external fun getNodeCount(): Int
+
+    /**
+     * Returns the index of the mesh the node instances, or -1 if it has none.
+     */
+    external fun getNodeMeshIndex(nodeIndex: Int): Int
+
+    /**
+     * Returns the index of the `KHR_lights_punctual` light of the node, or -1 if it has none.
+     */
+    external fun getNodeLightIndex(nodeIndex: Int): Int
+
+    /**
+     * Returns the (x, y, z) world-space position of the node in the pose of [state],
+     * with the constraints applied.
+     */
+    external fun getNodeWorldPosition(state: GltfAnimationState, nodeIndex: Int): FloatArray
+
+    /**
+     * Returns the (x, y, z) world-space direction the -Z axis of the node points at in the pose of
+     * [state], with the constraints applied. This is the direction of the node's directional or spot light.
+     */
+    external fun getNodeWorldDirection(state: GltfAnimationState, nodeIndex: Int): FloatArray
+
+    /**
+     * Returns the number of `EXT_mesh_gpu_instancing` instances of the node, or 0 if it is not instanced.
+     */
+    external fun getNodeInstanceCount(nodeIndex: Int): Int
+
+    /**
+     * Returns the local matrices of the node's instances, relative to the node,
+     * 16 floats each in column-major order.
+     */
+    external fun getNodeInstanceMatrices(nodeIndex: Int): FloatArray
+
+    private external fun getDrawItems0(state: GltfAnimationState, expandInstances: Boolean): FloatArray
+
+    /**
+     * Returns the meshes to draw in the scene, in the pose of [state] with the constraints applied.
+     * If [expandInstances] is true, each `EXT_mesh_gpu_instancing` instance is a draw item of its own;
+     * otherwise the instances should be drawn from [getNodeInstanceMatrices] relative to the item's matrix.
+     */
+    fun getDrawItems(state: GltfAnimationState, expandInstances: Boolean): List<GltfDrawItem> {
+        val data = getDrawItems0(state, expandInstances)
+        return (0 until data.size / GltfDrawItem.STRIDE).map {
+            val offset = it * GltfDrawItem.STRIDE
+            GltfDrawItem(
+                data[offset].toInt(),
+                data[offset + 1].toInt(),
+                data[offset + 2].toInt(),
+                data.copyOfRange(offset + 3, offset + GltfDrawItem.STRIDE)
+            )
+        }
+    }
+
+    external fun getLightCount(): Int
+
+    external fun getLightName(lightIndex: Int): String?
+
+    private external fun getLightType0(lightIndex: Int): Int
+
+    fun getLightType(lightIndex: Int) = GltfLightType.fromId(getLightType0(lightIndex))
+
+    // The light queries below return the values animated in the given state, if any,
+    // and the ones of the glTF otherwise.
+
+    private external fun getLightColor0(lightIndex: Int, state: GltfAnimationState?): FloatArray
+
+    /**
+     * Returns the linear (r, g, b) colour of the light.
+     */
+    fun getLightColor(lightIndex: Int, state: GltfAnimationState? = null) = getLightColor0(lightIndex, state)
+
+    private external fun getLightIntensity0(lightIndex: Int, state: GltfAnimationState?): Float
+
+    /**
+     * Returns the intensity of the light, in candela for point and spot lights and in lux
+     * for directional lights.
+     */
+    fun getLightIntensity(lightIndex: Int, state: GltfAnimationState? = null) = getLightIntensity0(lightIndex, state)
+
+    private external fun getLightRange0(lightIndex: Int, state: GltfAnimationState?): Float
+
+    /**
+     * Returns the range of the light, or [Float.POSITIVE_INFINITY] if it is not limited.
+     */
+    fun getLightRange(lightIndex: Int, state: GltfAnimationState? = null) = getLightRange0(lightIndex, state)
+
+    private external fun getLightConeAngles0(lightIndex: Int, state: GltfAnimationState?): FloatArray
+
+    /**
+     * Returns the inner and outer cone angles of a spot light, in radians.
+     */
+    fun getLightConeAngles(lightIndex: Int, state: GltfAnimationState? = null) = getLightConeAngles0(lightIndex, state)
+
+    external fun getAnimationCount(): Int
+
+    external fun getAnimationName(animationIndex: Int): String?
+
+    /**
+     * Returns the time of the last keyframe of the animation, in seconds.
+     */
+    external fun getAnimationDuration(animationIndex: Int): Float
+
+    /**
+     * Samples the animation at [time] (in seconds, clamped into the animation) into the animation
+     * [state] of one instance, overwriting what other animations wrote to the same targets. Node channels
+     * move the pose, see [evaluatePose], while `KHR_animation_pointer` channels targeting materials
+     * and lights are read back with [getAnimatedValue] and by the material and light queries taking
+     * the state. [GltfAnimationState.reset] goes back to the rest state.
+     */
+    external fun sampleAnimation(state: GltfAnimationState, animationIndex: Int, time: Float)
+
+    /**
+     * Returns the animated value in [state] of the property the `KHR_animation_pointer` JSON [pointer]
+     * points at (e.g. `/materials/0/emissiveFactor`), or an empty array if no sampled animation has written it.
+     */
+    external fun getAnimatedValue(state: GltfAnimationState, pointer: String): FloatArray
+
+    external fun getMeshCount(): Int
+
+    external fun getPrimitiveCount(meshIndex: Int): Int
+
+    external fun getMaterialCount(): Int
+
+    // The material queries below accept the index -1, standing for the default material
+    // the glTF spec applies to the primitives without one. Those taking an animation state
+    // read the factors and texture transforms animated in it, if given.
+
+    private external fun getMaterialAlphaMode0(index: Int): Int
+
+    fun getMaterialAlphaMode(index: Int) = GltfAlphaMode.fromId(getMaterialAlphaMode0(index))
+
+    private external fun getMaterialAlphaCutoff0(index: Int, state: GltfAnimationState?): Float
+
+    fun getMaterialAlphaCutoff(index: Int, state: GltfAnimationState? = null) = getMaterialAlphaCutoff0(index, state)
+
+    external fun isMaterialDoubleSided(index: Int): Boolean
+
+    external fun getMaterialName(index: Int): String?
+
+    external fun isMaterialUnlit(index: Int): Boolean
+
+    private external fun bakeMaterialAlbedo0(materialIndex: Int, state: GltfAnimationState?): ByteArray
+
+    /**
+     * Bakes the base colour, occlusion, metallic/roughness and emissive maps of the material into
+     * one albedo texture at the base colour resolution, for the vanilla shaders which only sample
+     * that. Returns the texture encoded as PNG, ready to be registered in place of the separate maps.
+     */
+    fun bakeMaterialAlbedo(materialIndex: Int, state: GltfAnimationState? = null) =
+        bakeMaterialAlbedo0(materialIndex, state)
+
+    private external fun packMaterialMaps0(materialIndex: Int, layoutId: Int, state: GltfAnimationState?): Image
+
+    /**
+     * Packs the maps of the material into a new image with the channel [layout], at the size of its
+     * largest texture. The factors, occlusion strength and normal scale are baked into the values.
+     */
+    fun packMaterialMaps(materialIndex: Int, layout: GltfChannelLayout, state: GltfAnimationState? = null) =
+        packMaterialMaps0(materialIndex, layout.id, state)
+
+    /**
+     * Chooses whether `KHR_materials_unlit` materials are rendered full-bright
+     * rather than with the vanilla entity lighting only.
+     */
+    external fun setUnlitFullBright(fullBright: Boolean)
+
+    private external fun getPrimitiveShadingHint0(meshIndex: Int, primitiveIndex: Int): Int
+
+    fun getPrimitiveShadingHint(meshIndex: Int, primitiveIndex: Int) =
+        GltfShadingHint.fromId(getPrimitiveShadingHint0(meshIndex, primitiveIndex))
+
+    private external fun getPrimitiveRenderClass0(
+        meshIndex: Int,
+        primitiveIndex: Int,
+        variantName: String?,
+        state: GltfAnimationState?
+    ): IntArray
+
+    /**
+     * Classifies the primitive, with the material it uses for the variant [variantName] (or its
+     * default material if [variantName] is null or unknown), into the render class telling which
+     * `RenderType` to draw it with and which texture to bind. The base colour alpha and the emission
+     * deciding it are the ones animated in [state], if given.
+     */
+    fun getPrimitiveRenderClass(
+        meshIndex: Int,
+        primitiveIndex: Int,
+        variantName: String? = null,
+        state: GltfAnimationState? = null
+    ): GltfRenderClass {
+        val data = getPrimitiveRenderClass0(meshIndex, primitiveIndex, variantName, state)
+        return GltfRenderClass(
+            GltfRenderCategory.fromId(data[0]),
+            GltfShadingHint.fromId(data[1]),
+            data[2],
+            if (data[2] >= 0) getTextureImageUri(data[2]) else null,
+            data[3] != 0,
+            data[4],
+            if (data[4] >= 0) getTextureImageUri(data[4]) else null
+        )
+    }
+
+    /**
+     * Returns the URI of the image the texture samples, or null if its image is not loaded from a URI.
+     */
+    external fun getTextureImageUri(textureIndex: Int): String?
+
+    private external fun getTextureSamplerParameters0(textureIndex: Int): IntArray
+
+    /**
+     * Returns the OpenGL parameters of the sampler of the texture, the default sampler if it has none.
+     */
+    fun getTextureSamplerParameters(textureIndex: Int) =
+        GltfSamplerParameters.fromArray(getTextureSamplerParameters0(textureIndex))
+
+    private external fun getImageSamplerParameters0(uriStr: String): IntArray
+
+    /**
+     * Returns the OpenGL parameters of the sampler of the first texture sampling the image loaded
+     * from the URI, for the image to be uploaded with.
+     */
+    fun getImageSamplerParameters(uriStr: String) =
+        GltfSamplerParameters.fromArray(getImageSamplerParameters0(uriStr))
+
+    private external fun getImageUsage0(uriStr: String): FloatArray
+
+    /**
+     * Returns how the materials use the image loaded from the URI, for its mipmaps to be generated
+     * in the right space and to keep the coverage of its cutout texels. When several `MASK`
+     * materials use it, the lowest alpha cutoff is kept.
+     */
+    fun getImageUsage(uriStr: String) = GltfImageUsage.fromArray(getImageUsage0(uriStr))
+
+    /**
+     * Returns the names of the `KHR_materials_variants` variants, in variant index order.
+     */
+    external fun getVariantNames(): Array<String>
+
+    /**
+     * Returns the material index the primitive uses for the variant [variantName], or for its
+     * default material if [variantName] is null or unknown. Returns -1 if there is no material,
+     * in which case the default material (index -1 of the material queries) applies.
+     */
+    external fun getPrimitiveMaterialForVariant(meshIndex: Int, primitiveIndex: Int, variantName: String?): Int
+
+    /**
+     * Returns the names of the material extensions used by the glTF that no native handler parses.
+     */
+    external fun getUnhandledMaterialExtensions(): Array<String>
+
+    private external fun getTextureTransformMatrix0(materialIndex: Int, slotId: Int, state: GltfAnimationState?): FloatArray
+
+    /**
+     * Returns the `KHR_texture_transform` UV matrix (column-major 3x3) of the material's texture
+     * in [slot], as animated in [state] if given, or an empty array if the material has no texture there.
+     */
+    fun getTextureTransformMatrix(materialIndex: Int, slot: GltfTextureSlot, state: GltfAnimationState? = null) =
+        getTextureTransformMatrix0(materialIndex, slot.id, state)
+
+    /**
+     * Returns the (x, y, z) positions of the primitive in mesh space. Quantized positions are
+     * dequantized by the world matrix of the node instancing the mesh, see [evaluatePose].
+     */
+    external fun getPrimitivePositions(meshIndex: Int, primitiveIndex: Int): FloatArray
+
+    /**
+     * Returns the (x, y, z) unit normals of the primitive in mesh space.
+     */
+    external fun getPrimitiveNormals(meshIndex: Int, primitiveIndex: Int): FloatArray
+
+    private external fun getPrimitiveColors0(
+        meshIndex: Int,
+        primitiveIndex: Int,
+        set: Int,
+        toSrgb: Boolean,
+        multiplyBaseColor: Boolean,
+        state: GltfAnimationState?
+    ): FloatArray
+
+    /**
+     * Returns the (r, g, b, a) colours of the COLOR_[set] attribute of the primitive, in linear space
+     * unless [toSrgb] is true (as Minecraft's vertex colour input expects), multiplied by the base
+     * colour factor of the material (as animated in [state], if given) if [multiplyBaseColor] is true.
+     * Returns an empty array if the primitive has no such attribute.
+     */
+    fun getPrimitiveColors(
+        meshIndex: Int,
+        primitiveIndex: Int,
+        set: Int,
+        toSrgb: Boolean,
+        multiplyBaseColor: Boolean,
+        state: GltfAnimationState? = null
+    ) = getPrimitiveColors0(meshIndex, primitiveIndex, set, toSrgb, multiplyBaseColor, state)
+
+    private external fun getPrimitiveTexCoords0(
+        meshIndex: Int,
+        primitiveIndex: Int,
+        bakeTextureTransform: Boolean,
+        state: GltfAnimationState?
+    ): FloatArray
+
+    /**
+     * Returns the (u, v) pairs of the texture coordinates the primitive's base colour texture samples,
+     * with its texture transform (as animated in [state], if given) baked in if [bakeTextureTransform] is true.
+     */
+    fun getPrimitiveTexCoords(
+        meshIndex: Int,
+        primitiveIndex: Int,
+        bakeTextureTransform: Boolean,
+        state: GltfAnimationState? = null
+    ) = getPrimitiveTexCoords0(meshIndex, primitiveIndex, bakeTextureTransform, state)
+
+    private external fun getPrimitiveAtlasTexCoords0(
+        meshIndex: Int,
+        primitiveIndex: Int,
+        atlas: TextureAtlas,
+        variantName: String?,
+        state: GltfAnimationState?
+    ): FloatArray?
+
+    /**
+     * Returns the (u, v) pairs the primitive's base colour texture samples, with its texture transform
+     * baked in, mapped into the space of the [atlas] page holding its image (which must be built).
+     * The texture is the one of the material the primitive uses for the variant [variantName], or of
+     * its default material if [variantName] is null or unknown, as in [getPrimitiveRenderClass], and
+     * its texture transform the one animated in [state], if given.
+     *
+     * An atlas cannot repeat an image: coordinates outside [0, 1] are clamped along the axes the sampler
+     * clamps to the edge, but along `REPEAT` or `MIRRORED_REPEAT` axes they make this return null, as it
+     * does when the image is not in the atlas. The primitive then has to keep binding its own texture.
+     */
+    fun getPrimitiveAtlasTexCoords(
+        meshIndex: Int,
+        primitiveIndex: Int,
+        atlas: TextureAtlas,
+        variantName: String? = null,
+        state: GltfAnimationState? = null
+    ) = getPrimitiveAtlasTexCoords0(meshIndex, primitiveIndex, atlas, variantName, state)
+
     override fun close() {
         nativeDestroy()
     }
@@ -0,0 +1,11 @@
+package top.srcres.mods.modelassetlib.gltf
+
+enum class GltfAlphaMode(val id: Int) {
+    Opaque(0),
+    Mask(1),
+    Blend(2);
+
+    companion object {
+        fun fromId(id: Int): GltfAlphaMode = entries.first { it.id == id }
+    }
+}
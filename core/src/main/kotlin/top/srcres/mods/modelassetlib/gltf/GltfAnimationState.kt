@@ -0,0 +1,29 @@
+package top.srcres.mods.modelassetlib.gltf
+
+/**
+ * The values the sampled animations wrote for one instance of a glTF model, e.g. one entity,
+ * on top of its rest state. Every instance drawn from the same [Gltf] owns its own state, passed
+ * to [Gltf.sampleAnimation] and to the getters reading the animated pose and properties.
+ * It also holds the targets of the constraints of the instance, see [Gltf.setLookAtAngles] and [Gltf.setIkTarget].
+ */
+class GltfAnimationState : AutoCloseable {
+    private var rust_animationStateObj: Long = 0L
+
+    init {
+        nativeInit()
+    }
+
+    /**
+     * Forgets everything the sampled animations wrote, going back to the rest state.
+     * The constraint targets are kept.
+     */
+    external fun reset()
+
+    private external fun nativeInit()
+
+    private external fun nativeDestroy()
+
+    override fun close() {
+        nativeDestroy()
+    }
+}
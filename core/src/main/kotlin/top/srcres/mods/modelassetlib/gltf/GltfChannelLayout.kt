@@ -0,0 +1,14 @@
+package top.srcres.mods.modelassetlib.gltf
+
+enum class GltfChannelLayout(val id: Int) {
+    /** Occlusion, roughness and metalness in red, green and blue, as glTF reads them. */
+    GltfOrm(0),
+    /** The LabPBR `_s` texture: smoothness, F0, porosity (left at 0) and emission. */
+    LabPbrSpecular(1),
+    /** The LabPBR `_n` texture: normal X and Y, occlusion and height (left flat). */
+    LabPbrNormal(2);
+
+    companion object {
+        fun fromId(id: Int): GltfChannelLayout = entries.first { it.id == id }
+    }
+}
@@ -0,0 +1,16 @@
+package top.srcres.mods.modelassetlib.gltf
+
+/**
+ * A mesh to draw, placed by [worldMatrix] (16 floats in column-major order).
+ * [instanceIndex] is -1 unless the item is one expanded `EXT_mesh_gpu_instancing` instance.
+ */
+class GltfDrawItem(
+    val nodeIndex: Int,
+    val meshIndex: Int,
+    val instanceIndex: Int,
+    val worldMatrix: FloatArray
+) {
+    companion object {
+        const val STRIDE = 19
+    }
+}
@@ -0,0 +1,15 @@
+package top.srcres.mods.modelassetlib.gltf
+
+/**
+ * How the materials of a glTF model use an image: whether it holds sRGB colours (base colour or
+ * emission) rather than linear data such as normals, and the alpha cutoff of the `MASK` material
+ * taking its base colour from it, null if there is none.
+ */
+class GltfImageUsage(
+    val color: Boolean,
+    val alphaCutoff: Float?
+) {
+    companion object {
+        fun fromArray(data: FloatArray) = GltfImageUsage(data[0] != 0f, data[1].takeIf { it >= 0f })
+    }
+}
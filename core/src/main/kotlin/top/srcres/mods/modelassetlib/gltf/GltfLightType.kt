@@ -0,0 +1,11 @@
+package top.srcres.mods.modelassetlib.gltf
+
+enum class GltfLightType(val id: Int) {
+    Directional(0),
+    Point(1),
+    Spot(2);
+
+    companion object {
+        fun fromId(id: Int): GltfLightType = entries.first { it.id == id }
+    }
+}
@@ -0,0 +1,19 @@
+package top.srcres.mods.modelassetlib.gltf
+
+enum class GltfObjectKind(val id: Int) {
+    Buffer(0),
+    BufferView(1),
+    Accessor(2),
+    Image(3),
+    Sampler(4),
+    Texture(5),
+    Material(6),
+    Mesh(7),
+    Node(8),
+    Animation(9),
+    Light(10);
+
+    companion object {
+        fun fromId(id: Int): GltfObjectKind = entries.first { it.id == id }
+    }
+}
@@ -0,0 +1,20 @@
+package top.srcres.mods.modelassetlib.gltf
+
+enum class GltfRenderCategory(val id: Int) {
+    /** Opaque and back-face culled, like `entitySolid`. */
+    Solid(0),
+    /** Alpha-tested and back-face culled, like `entityCutout`. */
+    Cutout(1),
+    /** Alpha-tested or opaque, without culling, like `entityCutoutNoCull`. */
+    CutoutNoCull(2),
+    /** Alpha-blended, like `entityTranslucent`. */
+    Translucent(3),
+    /** Glowing surfaces which do not depend on the lighting, like `eyes`. */
+    Emissive(4),
+    /** Unlit blended glow which adds to what is behind it, like `energySwirl`. */
+    Additive(5);
+
+    companion object {
+        fun fromId(id: Int): GltfRenderCategory = entries.first { it.id == id }
+    }
+}
@@ -0,0 +1,19 @@
+package top.srcres.mods.modelassetlib.gltf
+
+/**
+ * What the renderer needs to draw a primitive: the kind of `RenderType`, how to light it and the
+ * URI of the image to bind ([textureUri] is null if the primitive has no texture for its category).
+ *
+ * Lit emissive materials keep their category; if [emissiveLayer] is true, their emission has to be
+ * drawn over them as a second, `eyes`-like layer, with the [emissiveTextureUri] image (null if the
+ * emission is a plain colour).
+ */
+class GltfRenderClass(
+    val category: GltfRenderCategory,
+    val shadingHint: GltfShadingHint,
+    val textureIndex: Int,
+    val textureUri: String?,
+    val emissiveLayer: Boolean,
+    val emissiveTextureIndex: Int,
+    val emissiveTextureUri: String?
+)
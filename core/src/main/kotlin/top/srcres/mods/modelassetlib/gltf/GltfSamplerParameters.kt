@@ -0,0 +1,16 @@
+package top.srcres.mods.modelassetlib.gltf
+
+/**
+ * The OpenGL texture parameters a glTF sampler asks for, as `GL_*` enum values, with the filters
+ * it leaves undefined defaulting to `GL_LINEAR` and `GL_LINEAR_MIPMAP_LINEAR`.
+ */
+class GltfSamplerParameters(
+    val magFilter: Int,
+    val minFilter: Int,
+    val wrapS: Int,
+    val wrapT: Int
+) {
+    companion object {
+        fun fromArray(data: IntArray) = GltfSamplerParameters(data[0], data[1], data[2], data[3])
+    }
+}
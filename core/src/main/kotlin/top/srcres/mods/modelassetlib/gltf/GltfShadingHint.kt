@@ -0,0 +1,14 @@
+package top.srcres.mods.modelassetlib.gltf
+
+enum class GltfShadingHint(val id: Int) {
+    /** Regular diffuse lighting. */
+    Lit(0),
+    /** No diffuse lighting, only the vanilla entity lighting (light map and overlay). */
+    VanillaEntityLighting(1),
+    /** No lighting at all. */
+    FullBright(2);
+
+    companion object {
+        fun fromId(id: Int): GltfShadingHint = entries.first { it.id == id }
+    }
+}
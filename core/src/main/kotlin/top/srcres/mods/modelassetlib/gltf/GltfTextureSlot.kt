@@ -0,0 +1,9 @@
+package top.srcres.mods.modelassetlib.gltf
+
+enum class GltfTextureSlot(val id: Int) {
+    BaseColor(0),
+    MetallicRoughness(1),
+    Normal(2),
+    Occlusion(3),
+    Emissive(4)
+}
@@ -45,10 +45,11 @@ fun newExceptionFromNativeErrorMessage(prefixMessage: String): RuntimeException
     return RuntimeException("$prefixMessage: $msg")
 }
 
-class Image(rawData: ByteArray, format: Optional<ImageFormat>) : AutoCloseable {
-    constructor(rawData: ByteArray) : this(rawData, Optional.empty())
+class Image private constructor() : AutoCloseable {
+    private var rust_imageObj: Long = 0L
+    private var rust_imageMipLevelsObj: Long = 0L
 
-    init {
+    constructor(rawData: ByteArray, format: Optional<ImageFormat>) : this() {
         if (format.isEmpty) {
             if (!nativeInit(rawData)) {
                 throw newExceptionFromNativeErrorMessage("Failed to initialise native image").let {
@@ -67,7 +68,7 @@ class Image(rawData: ByteArray, format: Optional<ImageFormat>) : AutoCloseable {
         }
     }
 
-    private var rust_imageObj: Long = 0L
+    constructor(rawData: ByteArray) : this(rawData, Optional.empty())
 
     val width: Int
         get() {
@@ -101,6 +102,127 @@ class Image(rawData: ByteArray, format: Optional<ImageFormat>) : AutoCloseable {
             return result
         }
 
+    /**
+     * The number of mip levels of the image, including the full-sized one (level 0).
+     * Only KTX2 images may carry more than one level.
+     */
+    val mipLevelCount: Int
+        get() {
+            val n = getMipLevelCount0()
+            if (n < 0) {
+                throw newExceptionFromNativeErrorMessage("Failed to get image mip level count")
+            } else {
+                return n
+            }
+        }
+
+    fun getMipLevelWidth(level: Int): Int {
+        val n = getMipLevelWidth0(level)
+        if (n < 0) {
+            throw newExceptionFromNativeErrorMessage("Failed to get width of image mip level $level")
+        } else {
+            return n
+        }
+    }
+
+    fun getMipLevelHeight(level: Int): Int {
+        val n = getMipLevelHeight0(level)
+        if (n < 0) {
+            throw newExceptionFromNativeErrorMessage("Failed to get height of image mip level $level")
+        } else {
+            return n
+        }
+    }
+
+    fun getMipLevelRgbaData(level: Int): ByteArray {
+        val result = getMipLevelRgbaData0(level)
+        if (nativeIsErrorOccurred()) {
+            throw newExceptionFromNativeErrorMessage("Failed to get RGBA data of image mip level $level").let {
+                nativeClearError()
+                it
+            }
+        }
+        return result
+    }
+
+    /**
+     * Replaces the mip levels of the image with a full chain down to 1x1, each level being downsampled
+     * from the previous one with the [filter]. Colours are averaged in linear space if [gammaCorrect]
+     * (for sRGB textures), and the alpha of the smaller levels is scaled so that as many texels pass
+     * the [alphaCutoff] as in the full-sized one, if given, so that cutout textures do not thin out.
+     */
+    fun generateMipmaps(filter: MipmapFilter = MipmapFilter.Box, gammaCorrect: Boolean = true, alphaCutoff: Float? = null) {
+        if (!generateMipmaps0(filter.id, gammaCorrect, alphaCutoff ?: -1f)) {
+            throw newExceptionFromNativeErrorMessage("Failed to generate image mipmaps").let {
+                nativeClearError()
+                it
+            }
+        }
+    }
+
+    /**
+     * Resamples the image to [width] x [height] with the [filter], in linear space if [gammaCorrect]
+     * (except with [ResizeFilter.Nearest], which copies texels as they are). The mip levels are
+     * dropped since they would no longer match; generate them again if needed.
+     */
+    fun resize(width: Int, height: Int, filter: ResizeFilter = ResizeFilter.Lanczos, gammaCorrect: Boolean = true) {
+        if (!resize0(width, height, filter.id, gammaCorrect)) {
+            throw newExceptionFromNativeErrorMessage("Failed to resize image to ${width}x$height").let {
+                nativeClearError()
+                it
+            }
+        }
+    }
+
+    /**
+     * Resamples the image, like [resize], so that no side exceeds [maxSize] (if positive) while
+     * keeping its aspect, then with each side rounded to a power of two as per [rounding]. A side
+     * rounded up past [maxSize] is rounded down instead.
+     */
+    fun normalizeSize(
+        rounding: PowerOfTwoRounding = PowerOfTwoRounding.Next,
+        maxSize: Int = 0,
+        filter: ResizeFilter = ResizeFilter.Lanczos,
+        gammaCorrect: Boolean = true
+    ) {
+        if (!normalizeSize0(rounding.id, maxSize, filter.id, gammaCorrect)) {
+            throw newExceptionFromNativeErrorMessage("Failed to normalise image size").let {
+                nativeClearError()
+                it
+            }
+        }
+    }
+
+    /**
+     * Returns a new opaque greyscale image holding the [channel] of this one, e.g. to split
+     * an occlusion-roughness-metallic texture into separate maps.
+     */
+    fun extractChannel(channel: ImageChannel): Image {
+        val result = extractChannel0(channel.id)
+        if (result == null || nativeIsErrorOccurred()) {
+            throw newExceptionFromNativeErrorMessage("Failed to extract image channel $channel").let {
+                nativeClearError()
+                it
+            }
+        }
+        return result
+    }
+
+    /**
+     * Splits the image into one greyscale image per channel, in [ImageChannel] order.
+     * If a channel fails, the images already extracted are closed before rethrowing.
+     */
+    fun splitChannels(): List<Image> {
+        val result = mutableListOf<Image>()
+        try {
+            ImageChannel.entries.forEach { result.add(extractChannel(it)) }
+        } catch (e: Exception) {
+            result.forEach { it.close() }
+            throw e
+        }
+        return result
+    }
+
     private external fun nativeInit(rawData: ByteArray): Boolean
 
     private external fun nativeInitWithFormat(rawData: ByteArray, formatId: Int): Boolean
@@ -113,6 +235,22 @@ class Image(rawData: ByteArray, format: Optional<ImageFormat>) : AutoCloseable {
 
     private external fun getRgbaData0(): ByteArray
 
+    private external fun getMipLevelCount0(): Int
+
+    private external fun getMipLevelWidth0(level: Int): Int
+
+    private external fun getMipLevelHeight0(level: Int): Int
+
+    private external fun getMipLevelRgbaData0(level: Int): ByteArray
+
+    private external fun extractChannel0(channel: Int): Image?
+
+    private external fun generateMipmaps0(filterId: Int, gammaCorrect: Boolean, alphaCutoff: Float): Boolean
+
+    private external fun resize0(width: Int, height: Int, filterId: Int, gammaCorrect: Boolean): Boolean
+
+    private external fun normalizeSize0(roundingId: Int, maxSize: Int, filterId: Int, gammaCorrect: Boolean): Boolean
+
     override fun close() {
         nativeDestroy()
     }
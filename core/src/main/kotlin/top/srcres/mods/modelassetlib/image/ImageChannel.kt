@@ -0,0 +1,8 @@
+package top.srcres.mods.modelassetlib.image
+
+enum class ImageChannel(val id: Int) {
+    Red(0),
+    Green(1),
+    Blue(2),
+    Alpha(3)
+}
@@ -17,7 +17,8 @@ enum class ImageFormat(val id: Int) {
     OpenExr(11),
     Farbfeld(12),
     Avif(13),
-    Qoi(14);
+    Qoi(14),
+    Ktx2(15);
 
     companion object {
         fun fromExtension(ext: String): Optional<ImageFormat> {
@@ -38,6 +39,7 @@ enum class ImageFormat(val id: Int) {
                 "pbm", "pam", "ppm", "pgm" -> Pnm
                 "ff" -> Farbfeld
                 "qoi" -> Qoi
+                "ktx2" -> Ktx2
                 else -> null
             }
             return if (result == null) Optional.empty() else Optional.of(result)
@@ -0,0 +1,9 @@
+package top.srcres.mods.modelassetlib.image
+
+enum class MipmapFilter(val id: Int) {
+    /** Averages each 2x2 block, like OpenGL does. */
+    Box(0),
+    Triangle(1),
+    /** Keeps more detail than the others, at the price of some ringing. */
+    Lanczos(2)
+}
@@ -0,0 +1,8 @@
+package top.srcres.mods.modelassetlib.image
+
+enum class PowerOfTwoRounding(val id: Int) {
+    /** Leaves the sides as they are. */
+    Keep(0),
+    Next(1),
+    Previous(2)
+}
@@ -0,0 +1,10 @@
+package top.srcres.mods.modelassetlib.image
+
+enum class ResizeFilter(val id: Int) {
+    /** Keeps the texels sharp, for pixel art. */
+    Nearest(0),
+    Triangle(1),
+    CatmullRom(2),
+    /** Keeps the most detail, for everything but pixel art. */
+    Lanczos(3)
+}
@@ -0,0 +1,131 @@
+package top.srcres.mods.modelassetlib.image
+
+import top.srcres.mods.modelassetlib.gltf.Gltf
+
+/**
+ * Packs images, e.g. those of one or more glTF models, into power-of-two pages of at most
+ * [maxPageSize] pixels a side (itself a power of two), each image surrounded by [padding] pixels of its edge texels
+ * so that filtering and mip levels do not bleed its neighbours in.
+ *
+ * Add the images, [build] the atlas, then read the pages and where each image was placed.
+ * Primitive texture coordinates are mapped into atlas space by [Gltf.getPrimitiveAtlasTexCoords].
+ */
+class TextureAtlas(padding: Int = 4, maxPageSize: Int = 4096) : AutoCloseable {
+    private var rust_atlasObj: Long = 0L
+
+    init {
+        if (!nativeInit(padding, maxPageSize)) {
+            throw newExceptionFromNativeErrorMessage("Failed to initialise native texture atlas").let {
+                nativeClearError()
+                it
+            }
+        }
+    }
+
+    /**
+     * Adds a copy of the image under the key, replacing the image already under it.
+     * Empty images are refused.
+     */
+    fun addImage(key: String, image: Image) {
+        if (!addImage0(key, image)) {
+            throw newExceptionFromNativeErrorMessage("Failed to add image $key to texture atlas").let {
+                nativeClearError()
+                it
+            }
+        }
+    }
+
+    /**
+     * Adds every image of the glTF loaded from a URI, keyed by its URI.
+     */
+    fun addGltfImages(gltf: Gltf) {
+        for (uri in gltf.imageURIList) {
+            Image(gltf.getImageDataByURI(uri)).use { addImage(uri, it) }
+        }
+    }
+
+    operator fun contains(key: String): Boolean {
+        val result = contains0(key)
+        if (nativeIsErrorOccurred()) {
+            throw newExceptionFromNativeErrorMessage("Failed to look image $key up in texture atlas").let {
+                nativeClearError()
+                it
+            }
+        }
+        return result
+    }
+
+    /**
+     * Packs the images added so far into pages, replacing the pages of the previous build.
+     * Fails if an image does not fit in a page with its padding.
+     */
+    fun build() {
+        if (!build0()) {
+            throw newExceptionFromNativeErrorMessage("Failed to build texture atlas").let {
+                nativeClearError()
+                it
+            }
+        }
+    }
+
+    val pageCount: Int
+        get() {
+            val n = getPageCount0()
+            if (n < 0) {
+                throw newExceptionFromNativeErrorMessage("Failed to get texture atlas page count").let {
+                    nativeClearError()
+                    it
+                }
+            } else {
+                return n
+            }
+        }
+
+    /**
+     * Returns a new image holding a copy of the page.
+     */
+    fun getPageImage(page: Int): Image {
+        val result = getPageImage0(page)
+        if (result == null || nativeIsErrorOccurred()) {
+            throw newExceptionFromNativeErrorMessage("Failed to get texture atlas page $page").let {
+                nativeClearError()
+                it
+            }
+        }
+        return result
+    }
+
+    /**
+     * Returns where the image under the key was placed, or null if it is not in the atlas.
+     */
+    fun getPlacement(key: String): TextureAtlasPlacement? {
+        val data = getPlacement0(key)
+        if (nativeIsErrorOccurred()) {
+            throw newExceptionFromNativeErrorMessage("Failed to get placement of image $key in texture atlas").let {
+                nativeClearError()
+                it
+            }
+        }
+        return data?.let { TextureAtlasPlacement.fromArray(it) }
+    }
+
+    private external fun nativeInit(padding: Int, maxPageSize: Int): Boolean
+
+    private external fun nativeDestroy()
+
+    private external fun addImage0(key: String, image: Image): Boolean
+
+    private external fun contains0(key: String): Boolean
+
+    private external fun build0(): Boolean
+
+    private external fun getPageCount0(): Int
+
+    private external fun getPageImage0(page: Int): Image?
+
+    private external fun getPlacement0(key: String): IntArray?
+
+    override fun close() {
+        nativeDestroy()
+    }
+}
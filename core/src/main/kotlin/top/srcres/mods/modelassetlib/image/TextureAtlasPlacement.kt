@@ -0,0 +1,24 @@
+package top.srcres.mods.modelassetlib.image
+
+/**
+ * Where an image was placed in a [TextureAtlas]: its page and its rectangle in pixels, the padding
+ * around it excluded, along with the size of the page to get the rectangle in atlas UV space.
+ */
+class TextureAtlasPlacement(
+    val page: Int,
+    val x: Int,
+    val y: Int,
+    val width: Int,
+    val height: Int,
+    val pageWidth: Int,
+    val pageHeight: Int
+) {
+    val u0: Float get() = x.toFloat() / pageWidth
+    val v0: Float get() = y.toFloat() / pageHeight
+    val u1: Float get() = (x + width).toFloat() / pageWidth
+    val v1: Float get() = (y + height).toFloat() / pageHeight
+
+    companion object {
+        fun fromArray(data: IntArray) = TextureAtlasPlacement(data[0], data[1], data[2], data[3], data[4], data[5], data[6])
+    }
+}